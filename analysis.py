@@ -11,15 +11,17 @@ iterations = 0
 
 def get_score(row):
     try:
-        return int(row[116:])
-    except ValueError:
+        # any children of the address follow its reward
+        return int(row[116:].split()[0])
+    except (ValueError, IndexError):
         return 0
 
 while True:
     print(f'\n\n\nruntime: {round((time.time() - checkpoint) / 60, 2)} minutes')
 
     with open('./efficient_addresses.txt') as f:
-        content = f.readlines()
+        # skip the run header comments that record each run's parameters
+        content = [i for i in f.readlines() if not i.startswith('#')]
 
     d = [get_score(i) for i in content]
 
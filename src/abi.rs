@@ -0,0 +1,479 @@
+use crate::error::{Argument, ConfigError};
+use crate::parse;
+use alloy_primitives::{hex, U256};
+use std::str::FromStr;
+
+/// A Solidity type that constructor arguments can be given for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParamType {
+    Address,
+    Bool,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<ParamType>),
+    FixedArray(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+}
+
+/// A value ready to be ABI-encoded.
+#[derive(Debug)]
+enum Token {
+    /// A single, statically-sized 32-byte word.
+    Word([u8; 32]),
+    /// Dynamically-sized `bytes` or `string` data.
+    Bytes(Vec<u8>),
+    /// A dynamically-sized array, encoded with a length prefix.
+    Array(Vec<Token>),
+    /// A fixed-size array or a tuple, encoded in place if all of its members
+    /// are statically-sized.
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Word(_) => false,
+            Token::Bytes(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+        }
+    }
+
+    /// Size of the token in the head of an enclosing tuple.
+    fn head_len(&self) -> usize {
+        match self {
+            Token::Tuple(tokens) if !self.is_dynamic() => tokens.iter().map(Token::head_len).sum(),
+            _ => 32,
+        }
+    }
+}
+
+/// ABI-encode constructor arguments, given a signature such as
+/// `constructor(address,uint256,bytes)` and one string value per parameter.
+///
+/// Values are given as hex for `address`, `bytes` and `bytesN`, as decimal or
+/// `0x`-prefixed hex for integers, as `true` or `false` for `bool`, and
+/// verbatim for `string` (whitespace included). Arrays are written as `[a,b]`
+/// and tuples as `(a,b)`.
+pub(crate) fn encode_constructor_args(
+    signature: &str,
+    values: &[String],
+) -> Result<Vec<u8>, ConfigError> {
+    let types = parse_signature(signature).map_err(ConfigError::InvalidConstructorSig)?;
+    if types.len() != values.len() {
+        return Err(ConfigError::ConstructorArgCount {
+            expected: types.len(),
+            actual: values.len(),
+        });
+    }
+
+    let tokens = types
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(index, (kind, value))| {
+            tokenize(kind, value)
+                .map_err(|reason| ConfigError::InvalidConstructorArg { index, reason })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(encode_tuple(&tokens))
+}
+
+/// Parse the parameter types out of a signature, ignoring the function name.
+fn parse_signature(signature: &str) -> Result<Vec<ParamType>, &'static str> {
+    let signature = signature.trim();
+    let (Some(start), Some(params)) = (signature.find('('), signature.strip_suffix(')')) else {
+        return Err("expected a signature like `constructor(type,...)`");
+    };
+
+    split_top_level(&params[start + 1..])?
+        .into_iter()
+        .map(parse_type)
+        .collect()
+}
+
+fn parse_type(kind: &str) -> Result<ParamType, &'static str> {
+    let kind = kind.trim();
+
+    // array suffixes bind last, e.g. `uint256[2][]` is a dynamic array of pairs
+    if let Some(inner) = kind.strip_suffix(']') {
+        let Some(open) = inner.rfind('[') else {
+            return Err("unbalanced brackets");
+        };
+        let element = Box::new(parse_type(&inner[..open])?);
+        let size = &inner[open + 1..];
+        if size.is_empty() {
+            return Ok(ParamType::Array(element));
+        }
+        return match size.parse::<usize>() {
+            Ok(size) if size > 0 => Ok(ParamType::FixedArray(element, size)),
+            _ => Err("invalid fixed array size"),
+        };
+    }
+
+    if let Some(members) = kind.strip_prefix('(').and_then(|k| k.strip_suffix(')')) {
+        let members = split_top_level(members)?
+            .into_iter()
+            .map(parse_type)
+            .collect::<Result<_, _>>()?;
+        return Ok(ParamType::Tuple(members));
+    }
+
+    let sized = |prefix: &str, default: usize| -> Option<usize> {
+        let size = kind.strip_prefix(prefix)?;
+        if size.is_empty() {
+            Some(default)
+        } else {
+            size.parse().ok()
+        }
+    };
+
+    match kind {
+        "address" => Ok(ParamType::Address),
+        "bool" => Ok(ParamType::Bool),
+        "bytes" => Ok(ParamType::Bytes),
+        "string" => Ok(ParamType::String),
+        _ => {
+            if let Some(bits) = sized("uint", 256) {
+                if bits % 8 == 0 && (8..=256).contains(&bits) {
+                    return Ok(ParamType::Uint(bits));
+                }
+            } else if let Some(bits) = sized("int", 256) {
+                if bits % 8 == 0 && (8..=256).contains(&bits) {
+                    return Ok(ParamType::Int(bits));
+                }
+            } else if let Some(size) = sized("bytes", 0) {
+                if (1..=32).contains(&size) {
+                    return Ok(ParamType::FixedBytes(size));
+                }
+            }
+            Err("unsupported type")
+        }
+    }
+}
+
+/// Split a comma-separated list, ignoring commas nested in brackets. Items are
+/// left untrimmed, so that strings keep their whitespace.
+fn split_top_level(list: &str) -> Result<Vec<&str>, &'static str> {
+    if list.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![];
+    let mut depth: usize = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => {
+                let Some(d) = depth.checked_sub(1) else {
+                    return Err("unbalanced brackets");
+                };
+                depth = d;
+            }
+            ',' if depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err("unbalanced brackets");
+    }
+    items.push(&list[start..]);
+
+    Ok(items)
+}
+
+fn tokenize(kind: &ParamType, value: &str) -> Result<Token, &'static str> {
+    // strings are encoded verbatim, while whitespace around any other value
+    // is ignored
+    let value = match kind {
+        ParamType::String => value,
+        _ => value.trim(),
+    };
+
+    match kind {
+        ParamType::Address => {
+            let address =
+                parse::address(Argument::ConstructorArgs, value).map_err(|e| match e {
+                    ConfigError::InvalidChecksum { .. } => {
+                        "address does not match its EIP-55 checksum"
+                    }
+                    _ => "invalid address",
+                })?;
+            let mut word = [0; 32];
+            word[12..].copy_from_slice(&address);
+            Ok(Token::Word(word))
+        }
+        ParamType::Bool => match value {
+            "true" => Ok(Token::Word(U256::from(1).to_be_bytes())),
+            "false" => Ok(Token::Word([0; 32])),
+            _ => Err("expected true or false for bool"),
+        },
+        ParamType::Uint(bits) => {
+            let Ok(value) = U256::from_str(value) else {
+                return Err("invalid unsigned integer");
+            };
+            if value.bit_len() > *bits {
+                return Err("unsigned integer out of range");
+            }
+            Ok(Token::Word(value.to_be_bytes()))
+        }
+        ParamType::Int(bits) => {
+            let (negative, magnitude) = match value.strip_prefix('-') {
+                Some(magnitude) => (true, magnitude),
+                None => (false, value),
+            };
+            let Ok(magnitude) = U256::from_str(magnitude) else {
+                return Err("invalid signed integer");
+            };
+
+            // the range of an intN is -2^(N-1) ..= 2^(N-1) - 1
+            let bound = U256::from(1) << (bits - 1);
+            if magnitude > bound || (magnitude == bound && !negative) {
+                return Err("signed integer out of range");
+            }
+            let value = if negative {
+                magnitude.wrapping_neg()
+            } else {
+                magnitude
+            };
+            Ok(Token::Word(value.to_be_bytes()))
+        }
+        ParamType::FixedBytes(size) => {
+            let Ok(bytes) = hex::decode(value) else {
+                return Err("invalid hex bytes");
+            };
+            if bytes.len() != *size {
+                return Err("wrong length for fixed bytes");
+            }
+            let mut word = [0; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(Token::Word(word))
+        }
+        ParamType::Bytes => {
+            let Ok(bytes) = hex::decode(value) else {
+                return Err("invalid hex bytes");
+            };
+            Ok(Token::Bytes(bytes))
+        }
+        ParamType::String => Ok(Token::Bytes(value.as_bytes().to_vec())),
+        ParamType::Array(element) => {
+            let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+                return Err("expected an array like `[a,b]`");
+            };
+            let tokens = split_top_level(items)?
+                .into_iter()
+                .map(|item| tokenize(element, item))
+                .collect::<Result<_, _>>()?;
+            Ok(Token::Array(tokens))
+        }
+        ParamType::FixedArray(element, size) => {
+            let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) else {
+                return Err("expected an array like `[a,b]`");
+            };
+            let items = split_top_level(items)?;
+            if items.len() != *size {
+                return Err("wrong length for fixed array");
+            }
+            let tokens = items
+                .into_iter()
+                .map(|item| tokenize(element, item))
+                .collect::<Result<_, _>>()?;
+            Ok(Token::Tuple(tokens))
+        }
+        ParamType::Tuple(members) => {
+            let Some(items) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) else {
+                return Err("expected a tuple like `(a,b)`");
+            };
+            let items = split_top_level(items)?;
+            if items.len() != members.len() {
+                return Err("wrong number of tuple members");
+            }
+            let tokens = members
+                .iter()
+                .zip(items)
+                .map(|(member, item)| tokenize(member, item))
+                .collect::<Result<_, _>>()?;
+            Ok(Token::Tuple(tokens))
+        }
+    }
+}
+
+/// Encode a sequence of tokens as the head and tail of a tuple.
+fn encode_tuple(tokens: &[Token]) -> Vec<u8> {
+    let head_len: usize = tokens.iter().map(Token::head_len).sum();
+
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = vec![];
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&U256::from(head_len + tail.len()).to_be_bytes::<32>());
+            tail.extend(encode_token(token));
+        } else {
+            head.extend(encode_token(token));
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Word(word) => word.to_vec(),
+        Token::Bytes(bytes) => {
+            let mut encoded = U256::from(bytes.len()).to_be_bytes::<32>().to_vec();
+            encoded.extend_from_slice(bytes);
+            encoded.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+            encoded
+        }
+        Token::Array(tokens) => {
+            let mut encoded = U256::from(tokens.len()).to_be_bytes::<32>().to_vec();
+            encoded.extend(encode_tuple(tokens));
+            encoded
+        }
+        Token::Tuple(tokens) => encode_tuple(tokens),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(signature: &str, values: &[&str]) -> String {
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        hex::encode(encode_constructor_args(signature, &values).unwrap())
+    }
+
+    #[test]
+    fn static_ints_address_and_bool() {
+        let encoded = encode(
+            "constructor(uint256,uint8,address,bool)",
+            &[
+                "1000",
+                "255",
+                "0x0000000000000000000000000000000000000001",
+                "true",
+            ],
+        );
+        let expected = concat!(
+            "00000000000000000000000000000000000000000000000000000000000003e8",
+            "00000000000000000000000000000000000000000000000000000000000000ff",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn negative_ints() {
+        let encoded = encode("constructor(int256,int8,int24)", &["-1", "-128", "-5"]);
+        let expected = concat!(
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffb",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn dynamic_bytes_and_string() {
+        let encoded = encode(
+            "constructor(bytes,string,uint256)",
+            &["0xdeadbeef", "hello", "7"],
+        );
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000007",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "deadbeef00000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "68656c6c6f000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    // the example of the Solidity ABI specification
+    #[test]
+    fn dynamic_array_and_bytes() {
+        let encoded = encode(
+            "f(uint256,uint32[],bytes10,bytes)",
+            &[
+                "0x123",
+                "[0x456,0x789]",
+                "0x31323334353637383930",
+                "0x48656c6c6f2c20776f726c6421",
+            ],
+        );
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000123",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "3132333435363738393000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000000000000000000000000000000000e0",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000456",
+            "0000000000000000000000000000000000000000000000000000000000000789",
+            "000000000000000000000000000000000000000000000000000000000000000d",
+            "48656c6c6f2c20776f726c642100000000000000000000000000000000000000",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn tuple_arrays() {
+        let encoded = encode(
+            "constructor((address,uint256)[],(uint256,string)[2])",
+            &["[(0x0000000000000000000000000000000000000002,5),(0x0000000000000000000000000000000000000003,6)]", "[(1,one),(2,two)]"],
+        );
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000000e0",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "0000000000000000000000000000000000000000000000000000000000000006",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000000c0",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "6f6e650000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "74776f0000000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn strings_keep_their_whitespace() {
+        let encoded = encode("constructor(string,uint8[])", &[" padded ", " [ 1, 2 ] "]);
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000008",
+            "2070616464656420000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+        );
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn out_of_range_ints() {
+        let values = |value: &str| vec![value.to_string()];
+        assert!(encode_constructor_args("constructor(int8)", &values("-129")).is_err());
+        assert!(encode_constructor_args("constructor(int8)", &values("128")).is_err());
+        assert!(encode_constructor_args("constructor(uint8)", &values("256")).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::cli::AccountArgs;
+use crate::error::{Argument, ConfigError};
+use crate::salt::SaltHash;
+use crate::{abi, clone, init_code, keccak256, parse};
+use alloy_primitives::hex;
+use serde::Deserialize;
+
+/// An ERC-4337 smart account factory whose `getAddress` is reproduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccountFactory {
+    /// eth-infinitism's `SimpleAccountFactory`, which deploys an ERC1967Proxy
+    /// of the account implementation via CREATE2 with the salt it is given,
+    /// initialized with `initialize(owner)`.
+    SimpleAccount,
+    /// ZeroDev's Kernel v3 `KernelFactory`, which deploys a Solady ERC-1967
+    /// proxy of the implementation via CREATE2 with a salt of
+    /// `keccak256(data ++ salt)`.
+    Kernel,
+}
+
+/// The init code and hashed salt that a smart account factory derives the
+/// address of an account from, other than the salt.
+pub(crate) struct AccountPreset {
+    pub(crate) init_code: Vec<u8>,
+    pub(crate) hashed_salt: Option<SaltHash>,
+}
+
+impl AccountPreset {
+    pub(crate) fn new(args: &AccountArgs, factory: AccountFactory) -> Result<Self, ConfigError> {
+        let Some(implementation) = &args.implementation else {
+            return Err(ConfigError::Requires(
+                Argument::Account,
+                Argument::AccountImplementation,
+            ));
+        };
+        let implementation = parse::address(Argument::AccountImplementation, implementation)?;
+
+        match factory {
+            AccountFactory::SimpleAccount => {
+                let Some(proxy_code) = &args.proxy_code else {
+                    return Err(ConfigError::Requires(
+                        Argument::Account,
+                        Argument::AccountProxyCode,
+                    ));
+                };
+                let Some(owner) = &args.owner else {
+                    return Err(ConfigError::Requires(
+                        Argument::Account,
+                        Argument::AccountOwner,
+                    ));
+                };
+                let proxy_code = init_code::read(Argument::AccountProxyCode, proxy_code)?;
+                let owner = parse::address(Argument::AccountOwner, owner)?;
+
+                Ok(Self {
+                    init_code: simple_account_init_code(proxy_code, &implementation, &owner)?,
+                    hashed_salt: None,
+                })
+            }
+            AccountFactory::Kernel => {
+                let Some(init_data) = &args.init_data else {
+                    return Err(ConfigError::Requires(
+                        Argument::Account,
+                        Argument::AccountInitData,
+                    ));
+                };
+                let init_data = parse::hex(Argument::AccountInitData, init_data)?;
+
+                Ok(Self {
+                    init_code: clone::erc1967_init_code(&implementation),
+                    hashed_salt: Some(SaltHash::new(init_data, vec![])),
+                })
+            }
+        }
+    }
+}
+
+/// Build the init code that `SimpleAccountFactory` deploys: the creation code
+/// of its ERC1967Proxy, with the implementation and the call data of
+/// `initialize(owner)` ABI-encoded as its constructor arguments.
+fn simple_account_init_code(
+    mut proxy_code: Vec<u8>,
+    implementation: &[u8; 20],
+    owner: &[u8; 20],
+) -> Result<Vec<u8>, ConfigError> {
+    let mut initialize = keccak256(b"initialize(address)")[..4].to_vec();
+    initialize.extend_from_slice(&[0; 12]);
+    initialize.extend_from_slice(owner);
+
+    proxy_code.extend(abi::encode_constructor_args(
+        "constructor(address,bytes)",
+        &[hex::encode(implementation), hex::encode(initialize)],
+    )?);
+    Ok(proxy_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::config;
+    use alloy_primitives::Address;
+
+    const FACTORY: &str = "0xfafafafafafafafafafafafafafafafafafafafa";
+    const IMPLEMENTATION: &str = "0x1111111111111111111111111111111111111111";
+    const OWNER: &str = "0x2222222222222222222222222222222222222222";
+
+    /// Stands in for the creation code of `ERC1967Proxy`, which ignores the
+    /// constructor arguments appended to it.
+    const PROXY_CODE: &str = "0x600a600c600039600a6000f3602a60005260206000f3";
+
+    /// A salt holding the given index, as `uint256` or `bytes32`.
+    fn index(index: u8) -> [u8; 32] {
+        let mut salt = [0; 32];
+        salt[31] = index;
+        salt
+    }
+
+    #[test]
+    fn simple_account_appends_initialize_call() {
+        let owner = parse::address(Argument::AccountOwner, OWNER).unwrap();
+        let implementation = [0x11; 20];
+        let init_code = simple_account_init_code(vec![0x60, 0x80], &implementation, &owner);
+
+        // abi.encode(implementation, abi.encodeCall(initialize, (owner)))
+        let expected = concat!(
+            "6080",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000024",
+            "c4d66de80000000000000000000000002222222222222222222222222222222222222222",
+            "00000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(init_code.unwrap()), expected);
+    }
+
+    #[test]
+    fn simple_account_get_address() {
+        // getAddress(owner, 7): the address that the factory deployed the
+        // proxy code with those constructor arguments to on an EVM
+        let search = config(&[
+            "--account",
+            "simple-account",
+            "--factory",
+            FACTORY,
+            "--account-implementation",
+            IMPLEMENTATION,
+            "--account-owner",
+            OWNER,
+            "--account-proxy-code",
+            PROXY_CODE,
+        ])
+        .unwrap();
+        assert_eq!(search.hashed_salt, None);
+        assert_eq!(
+            crate::derive_address(&search, &index(7)),
+            "0xf90d55c8446bbF896b16E474D0cA6B6e0dAa6A42"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn kernel_get_address() {
+        // getAddress(data, bytes32(3)): the Solady ERC-1967 proxy of the
+        // implementation deployed on an EVM with a salt of
+        // keccak256(abi.encodePacked(data, salt)), which stored the
+        // implementation in its ERC-1967 slot and delegated calls to it
+        let data = "0xc5a8b1a5000000000000000000000000222222222222222222222222222222222222222201";
+        let search = config(&[
+            "--account",
+            "kernel",
+            "--factory",
+            FACTORY,
+            "--account-implementation",
+            IMPLEMENTATION,
+            "--account-init-data",
+            data,
+        ])
+        .unwrap();
+        assert_eq!(
+            search.hashed_salt.as_ref().unwrap().hash(&index(3)),
+            hex!("71e6242df3c286af7f76d2edc5040ddc6da6e9098da5409ff01fdd55239eec48")
+        );
+        assert_eq!(
+            crate::derive_address(&search, &index(3)),
+            "0xD1cd1fDb196D0743cB4fbaFc7799F23B361C599B"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+}
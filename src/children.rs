@@ -0,0 +1,123 @@
+use crate::derivation::create_address;
+use crate::error::ConfigError;
+use crate::scorer::{self, Scorer};
+use crate::target::Quality;
+use alloy_primitives::Address;
+use std::fmt::Write as _;
+
+/// A contract that the deployed contract deploys via CREATE in its
+/// constructor, with the given nonce (contracts begin at a nonce of 1), and
+/// the thresholds that its address must meet for a salt to be reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Child {
+    pub nonce: u64,
+    pub leading_zeroes_threshold: u8,
+    pub total_zeroes_threshold: u8,
+}
+
+impl Child {
+    /// Parse comma-separated children like `1,2:2/4`, each a nonce optionally
+    /// followed by the leading and total zero bytes that its address must
+    /// have (either one suffices); children without thresholds of their own
+    /// take the given thresholds of the parent.
+    pub(crate) fn parse_list(
+        value: &str,
+        leading_zeroes_threshold: u8,
+        total_zeroes_threshold: u8,
+    ) -> Result<Vec<Self>, ConfigError> {
+        let invalid = ConfigError::InvalidChildren;
+        let number = |value: &str| value.trim().parse::<u64>().ok();
+
+        let mut children: Vec<Self> = vec![];
+        for child in value.split(',') {
+            let (nonce, thresholds) = match child.split_once(':') {
+                Some((nonce, thresholds)) => (nonce, Some(thresholds)),
+                None => (child, None),
+            };
+            let nonce =
+                number(nonce).ok_or_else(|| invalid("expected a nonce like `1` or `1:2/4`"))?;
+            if nonce == 0 {
+                return Err(invalid("contracts deploy their children from a nonce of 1"));
+            }
+            if children.iter().any(|child| child.nonce == nonce) {
+                return Err(invalid("a nonce is given more than once"));
+            }
+
+            let (leading, total) = match thresholds {
+                Some(thresholds) => {
+                    let threshold = |value: &str| {
+                        number(value)
+                            .and_then(|value| u8::try_from(value).ok())
+                            .ok_or_else(|| invalid("expected thresholds like `2/4`"))
+                    };
+                    let (leading, total) = thresholds
+                        .split_once('/')
+                        .ok_or_else(|| invalid("expected thresholds like `2/4`"))?;
+                    (threshold(leading)?, threshold(total)?)
+                }
+                None => (leading_zeroes_threshold, total_zeroes_threshold),
+            };
+            if leading > 20 || (total > 20 && total != 255) {
+                return Err(invalid(
+                    "thresholds must be 0..=20 leading and 0..=20 (or 255) total zero bytes",
+                ));
+            }
+
+            children.push(Self {
+                nonce,
+                leading_zeroes_threshold: leading,
+                total_zeroes_threshold: total,
+            });
+        }
+
+        Ok(children)
+    }
+
+    /// The leading and total zero bytes that the address of the child must
+    /// have (either one suffices).
+    pub(crate) fn threshold(&self) -> Quality {
+        Quality {
+            leading_zeroes: self.leading_zeroes_threshold,
+            total_zeroes: self.total_zeroes_threshold,
+        }
+    }
+
+    /// Derive the address of the child deployed by the given parent.
+    pub(crate) fn address(&self, parent: &Address) -> Address {
+        create_address(parent.as_slice(), self.nonce)
+    }
+}
+
+/// Count the leading and total zero bytes of an address.
+pub(crate) fn zero_bytes(address: &Address) -> (usize, usize) {
+    let leading = address.iter().take_while(|&&b| b == 0).count();
+    let total = address.iter().filter(|&&b| b == 0).count();
+    (leading, total)
+}
+
+/// Derive the address of every child of the parent, returning `None` if any
+/// of them misses its thresholds, and otherwise each child's nonce, address
+/// and score as appended to the parent's result, e.g.
+/// ` | nonce 1 => 0x... => 4 | nonce 2 => 0x... => 0`.
+pub(crate) fn describe(
+    children: &[Child],
+    parent: &Address,
+    scorer: &dyn Scorer,
+) -> Option<String> {
+    let mut description = String::new();
+    for child in children {
+        let address = child.address(parent);
+        if !scorer.reaches(&address, child.threshold()) {
+            return None;
+        }
+
+        let score = scorer::label(scorer, &address);
+        write!(
+            description,
+            " | nonce {} => {address} => {score}",
+            child.nonce
+        )
+        .unwrap();
+    }
+    Some(description)
+}
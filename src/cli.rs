@@ -0,0 +1,444 @@
+use crate::account::AccountFactory;
+use crate::clone::CloneKind;
+use crate::createx::CreatexGuard;
+use crate::factory::FactoryPreset;
+use crate::safe::SafeVersion;
+use crate::salt::UnboundSalt;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Find salts that create gas-efficient Ethereum addresses via CREATE2.
+#[derive(Debug, Parser)]
+#[command(name = "create2crunch", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Search for salts that result in gas-efficient addresses.
+    Search(SearchArgs),
+    /// Compute the address and reward for a single salt.
+    Verify(VerifyArgs),
+    /// Check that the salts in the output file are valid for the current init
+    /// code, reporting every result that has gone stale.
+    Check(SearchArgs),
+    /// Search for private keys of fresh accounts whose CREATE deployments land
+    /// at gas-efficient addresses.
+    Deployer(DeployerArgs),
+    /// List the upcoming nonces of an existing account whose CREATE
+    /// deployments land at gas-efficient addresses.
+    Plan(PlanArgs),
+    /// List the available OpenCL platforms and devices.
+    Devices,
+    /// Print the init code and init code hash of a minimal proxy clone.
+    InitCode(CloneArgs),
+    /// Search for init code that a fixed salt deploys to a gas-efficient
+    /// address via CREATE2, by changing a range of its bytes.
+    Mutate(MutateArgs),
+    /// Measure the attempt rate of the CPU or of an OpenCL device.
+    Bench(BenchArgs),
+}
+
+/// Arguments describing a search, validated by `Config::new`. Any argument
+/// that is not given on the command line is taken from the profile selected
+/// with `--profile`, if any.
+#[derive(Debug, Clone, Args)]
+pub struct SearchArgs {
+    /// Name of the profile to load from the config file.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// TOML file holding the profiles.
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "profile",
+        default_value = "create2crunch.toml"
+    )]
+    pub config: PathBuf,
+
+    /// Address of the contract that will be calling CREATE2.
+    #[arg(long, value_name = "ADDRESS")]
+    pub factory: Option<String>,
+
+    /// Well-known factory to search salts for, which supplies the factory
+    /// address and refuses salts that it would revert on: arachnid,
+    /// immutable-create2, eip2470 or createx (with --createx).
+    #[arg(long, value_name = "PRESET", conflicts_with = "factory")]
+    pub factory_preset: Option<FactoryPreset>,
+
+    /// Address of the caller of the factory, which begins each salt (use
+    /// --unbound-salt instead if the factory has no frontrunning protection).
+    #[arg(long, value_name = "ADDRESS")]
+    pub caller: Option<String>,
+
+    /// Keccak-256 hash of the initialization code of the contract.
+    #[arg(long, value_name = "HASH", conflicts_with = "clone")]
+    pub init_code_hash: Option<String>,
+
+    /// Initialization code of the contract, hex-encoded or as a file path,
+    /// hashed in place of --init-code-hash.
+    #[arg(
+        long,
+        value_name = "HEX|PATH",
+        conflicts_with_all = ["init_code_hash", "clone"]
+    )]
+    pub init_code: Option<String>,
+
+    /// Foundry or Hardhat build artifact to read the initialization code of
+    /// the contract from, hashed in place of --init-code-hash.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["init_code_hash", "init_code", "clone"]
+    )]
+    pub artifact: Option<PathBuf>,
+
+    /// Search CREATE3 addresses, which depend on the init code of the proxy
+    /// deployed via CREATE2 rather than that of the contract: a preset
+    /// (solady, solmate, sequence or createx) or the proxy's init code hash.
+    #[arg(
+        long,
+        value_name = "PRESET|HASH",
+        conflicts_with_all = ["init_code_hash", "init_code", "artifact", "clone"]
+    )]
+    pub create3: Option<String>,
+
+    /// Derive zkSync Era CREATE2 addresses, with --init-code or --artifact
+    /// giving the zksolc bytecode (e.g. a `.zbin` file) and --init-code-hash
+    /// its versioned bytecode hash, and --constructor-args the constructor
+    /// input rather than being appended to the bytecode.
+    #[arg(long, conflicts_with_all = ["create3", "createx", "clone", "version", "preset"])]
+    pub zksync: bool,
+
+    /// Derive EVM addresses even if the profile enables --zksync.
+    #[arg(long, conflicts_with = "zksync")]
+    pub no_zksync: bool,
+
+    /// Byte that the CREATE2 preimage begins with [default: 0xff].
+    #[arg(long, value_name = "BYTE", conflicts_with = "tron")]
+    pub create2_prefix: Option<String>,
+
+    /// Derive Tron (TVM) CREATE2 addresses, which use a prefix byte of 0x41,
+    /// and show each address found in base58 as well (--factory and --caller
+    /// may also be given in base58).
+    #[arg(long, conflicts_with_all = ["create3", "createx", "zksync", "version", "preset"])]
+    pub tron: bool,
+
+    /// Derive EVM addresses even if the profile enables --tron.
+    #[arg(long, conflicts_with = "tron")]
+    pub no_tron: bool,
+
+    /// Constructor signature used to ABI-encode --constructor-args and append
+    /// them to --init-code or --artifact, e.g. "constructor(address,uint256,bytes)".
+    #[arg(long, value_name = "SIGNATURE", conflicts_with = "clone")]
+    pub constructor_sig: Option<String>,
+
+    /// Constructor argument values, one per parameter of --constructor-sig.
+    #[arg(long, value_name = "VALUE", num_args = 1.., allow_negative_numbers = true)]
+    pub constructor_args: Vec<String>,
+
+    /// How each salt is built from comma-separated regions: `caller`,
+    /// `zero:N`, `fixed:HEX`, `random:N` and one `counter:N` (8 bytes on the
+    /// GPU), totalling 32 bytes [default: caller,random:6,counter:6, or
+    /// caller,random:4,counter:8 on the GPU].
+    #[arg(long, value_name = "TEMPLATE")]
+    pub salt_layout: Option<String>,
+
+    /// Search salts that are not bound to a caller, with the first 20 bytes
+    /// zero or random, for factories without frontrunning protection. Anyone
+    /// watching the mempool can steal such a salt!
+    #[arg(long, value_name = "MODE", conflicts_with_all = ["caller", "salt_layout"])]
+    pub unbound_salt: Option<UnboundSalt>,
+
+    /// For factories that hash the salt they are given into the CREATE2 salt:
+    /// the preimage of that hash, from comma-separated parts `salt` (the salt
+    /// searched for), `fixed:HEX` and `keccak:HEX`, e.g. `keccak:0x...,salt`.
+    #[arg(long, value_name = "TEMPLATE")]
+    pub hashed_salt: Option<String>,
+
+    /// Search salts for CreateX's `deployCreate2` (or, with --create3 createx,
+    /// `deployCreate3`), guarded the way CreateX guards them: with the salt
+    /// bound to the caller (sender), the chain (chain), both (sender-chain) or
+    /// neither (none).
+    #[arg(
+        long,
+        value_name = "GUARD",
+        conflicts_with_all = ["salt_layout", "unbound_salt", "hashed_salt", "version", "preset"]
+    )]
+    pub createx: Option<CreatexGuard>,
+
+    /// Chain that CreateX salts with cross-chain redeploy protection are
+    /// deployed on.
+    #[arg(long, value_name = "ID", requires = "createx")]
+    pub chain_id: Option<u64>,
+
+    /// Children that the contract deploys via CREATE in its constructor,
+    /// whose addresses must meet thresholds of their own for a salt to be
+    /// reported: comma-separated nonces, each optionally followed by its
+    /// leading/total zero byte thresholds, e.g. `1,2:2/4` (by default those
+    /// of the contract itself).
+    #[arg(long, value_name = "NONCES", conflicts_with_all = ["zksync", "tron"])]
+    pub children: Option<String>,
+
+    /// Another deployment of the contract that each salt must give an address
+    /// meeting the thresholds for as well, e.g. on a chain where the factory
+    /// or the compiled init code differs: `FACTORY,CALLER,INIT_CODE_HASH`,
+    /// optionally followed by `create2`, `create3` or `zksync` [default: the
+    /// derivation of the search]. May be given more than once.
+    #[arg(
+        long = "deployment",
+        value_name = "DEPLOYMENT",
+        conflicts_with_all = ["createx", "tron", "create2_prefix", "children"]
+    )]
+    pub deployments: Vec<String>,
+
+    /// OpenCL device to search with; the CPU is used when omitted.
+    #[arg(long, value_name = "DEVICE")]
+    pub gpu_device: Option<u8>,
+
+    /// Search with the CPU even if the profile specifies an OpenCL device.
+    #[arg(long, conflicts_with = "gpu_device")]
+    pub cpu: bool,
+
+    /// Minimum number of leading zero bytes reported by the GPU, or required
+    /// of the contract and (by default) its children with --children (0..=20)
+    /// [default: 3].
+    #[arg(long, value_name = "N")]
+    pub leading_zeroes: Option<u8>,
+
+    /// Minimum number of total zero bytes reported by the GPU, or required of
+    /// the contract and (by default) its children with --children (0..=20, or
+    /// 255 to disable) [default: 5].
+    #[arg(long, value_name = "N")]
+    pub total_zeroes: Option<u8>,
+
+    /// File that found salts are appended to [default:
+    /// efficient_addresses.txt].
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Leading/total zero bytes (either one suffices) at which the search
+    /// stops once an address reaching them is found, e.g. `4/8`.
+    #[arg(long, value_name = "L/T")]
+    pub until: Option<String>,
+
+    /// Another contract deployed by the factory to search for in the same
+    /// pass, with salts appended to its own output file: comma-separated
+    /// `init_code_hash` (or `init_code`), `caller` [default: --caller],
+    /// `output` and `until` fields, e.g.
+    /// `init_code_hash=0x...,output=vault.txt,until=4/8`. May be given more
+    /// than once.
+    #[arg(
+        long = "target",
+        value_name = "TARGET",
+        conflicts_with_all = ["create3", "zksync", "children", "deployments"]
+    )]
+    pub targets: Vec<String>,
+
+    #[command(flatten)]
+    pub safe: SafeArgs,
+
+    #[command(flatten)]
+    pub account: AccountArgs,
+
+    #[command(flatten)]
+    pub clone: CloneArgs,
+}
+
+/// Arguments for the minimal proxy clones, whose init code is built from the
+/// implementation (and any immutable args) in place of --init-code.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Minimal proxy clones")]
+pub struct CloneArgs {
+    /// Search for the salt of a minimal proxy clone of the given kind.
+    #[arg(id = "clone", long = "clone", value_name = "KIND")]
+    pub kind: Option<CloneKind>,
+
+    /// Implementation that the clone delegates to.
+    #[arg(
+        id = "clone_implementation",
+        long = "clone-implementation",
+        value_name = "ADDRESS"
+    )]
+    pub implementation: Option<String>,
+
+    /// Immutable args appended to the runtime code of the clone (erc1167).
+    #[arg(id = "clone_args", long = "clone-args", value_name = "HEX")]
+    pub immutable_args: Option<String>,
+}
+
+/// Arguments for the ERC-4337 smart account factory presets, which supply the
+/// init code (and hashed salt) of the account that the factory given with
+/// --factory deploys, with the salt searched for as the salt passed to it.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Smart account factory")]
+pub struct AccountArgs {
+    /// Search for the salt of an account deployed by the given kind of
+    /// factory.
+    #[arg(
+        long = "account",
+        value_name = "FACTORY",
+        conflicts_with_all = ["init_code_hash", "init_code", "artifact", "create3", "hashed_salt", "version", "clone"]
+    )]
+    pub preset: Option<AccountFactory>,
+
+    /// Account implementation that the factory deploys proxies of.
+    #[arg(long = "account-implementation", value_name = "ADDRESS")]
+    pub implementation: Option<String>,
+
+    /// Owner of the account (simple-account).
+    #[arg(long = "account-owner", value_name = "ADDRESS")]
+    pub owner: Option<String>,
+
+    /// Creation code of the factory's ERC1967Proxy, hex-encoded or as a file
+    /// path (simple-account).
+    #[arg(
+        id = "account_proxy_code",
+        long = "account-proxy-code",
+        value_name = "HEX|PATH"
+    )]
+    pub proxy_code: Option<String>,
+
+    /// Init data passed to the factory along with the salt (kernel).
+    #[arg(long = "account-init-data", value_name = "HEX")]
+    pub init_data: Option<String>,
+}
+
+/// Arguments for the Safe proxy factory preset, which supplies the factory
+/// (unless --factory is given for a chain without the canonical deployment),
+/// the init code and the hashed salt of `createProxyWithNonce`, with the salt
+/// searched for as its `saltNonce`.
+#[derive(Debug, Clone, Default, Args)]
+#[command(next_help_heading = "Safe proxy factory")]
+pub struct SafeArgs {
+    /// Search for the `saltNonce` of a Safe deployed by the proxy factory of
+    /// the given version.
+    #[arg(
+        long = "safe",
+        value_name = "VERSION",
+        conflicts_with_all = ["init_code_hash", "init_code", "artifact", "create3", "hashed_salt", "clone"]
+    )]
+    pub version: Option<SafeVersion>,
+
+    /// Creation code of the Safe proxy, as returned by the factory's
+    /// `proxyCreationCode()`, hex-encoded or as a file path.
+    #[arg(long = "safe-proxy-code", value_name = "HEX|PATH")]
+    pub proxy_code: Option<String>,
+
+    /// Safe singleton that the proxy delegates to [default: the canonical
+    /// singleton of the version].
+    #[arg(long = "safe-singleton", value_name = "ADDRESS")]
+    pub singleton: Option<String>,
+
+    /// Use the canonical SafeL2 singleton of the version.
+    #[arg(long = "safe-l2", conflicts_with = "singleton")]
+    pub l2: bool,
+
+    /// Call data of the `initializer` passed to the factory.
+    #[arg(
+        long = "safe-initializer",
+        value_name = "HEX",
+        conflicts_with = "owners"
+    )]
+    pub initializer: Option<String>,
+
+    /// Owners of the Safe, to build the `setup` call used as the initializer.
+    #[arg(long = "safe-owners", value_name = "ADDRESS", num_args = 1..)]
+    pub owners: Vec<String>,
+
+    /// Number of owners required to confirm a transaction [default: 1].
+    #[arg(long = "safe-threshold", value_name = "N", requires = "owners")]
+    pub threshold: Option<u64>,
+
+    /// Fallback handler set by `setup` [default: the canonical
+    /// CompatibilityFallbackHandler of the version].
+    #[arg(
+        long = "safe-fallback-handler",
+        value_name = "ADDRESS",
+        requires = "owners"
+    )]
+    pub fallback_handler: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    /// The 32-byte salt to derive the address for.
+    #[arg(long, value_name = "SALT")]
+    pub salt: String,
+}
+
+#[derive(Debug, Args)]
+pub struct DeployerArgs {
+    /// Nonce of the deployment, or a range of nonces like `0..4` or `0..=3`.
+    #[arg(long, value_name = "NONCES", default_value = "0")]
+    pub nonces: String,
+
+    /// File that the private keys of found deployers are appended to,
+    /// readable only by its owner [default: deployer_keys.txt].
+    #[arg(long, value_name = "PATH")]
+    pub keys_output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    /// Address of the existing deployer account.
+    #[arg(long, value_name = "ADDRESS")]
+    pub deployer: String,
+
+    /// Current nonce of the deployer account (its next transaction's nonce).
+    #[arg(long, value_name = "NONCE")]
+    pub nonce: u64,
+
+    /// How many upcoming nonces to scan.
+    #[arg(long, value_name = "N", default_value_t = 10_000)]
+    pub count: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct MutateArgs {
+    /// Initialization code of the contract, hex-encoded or as a file path,
+    /// including any constructor arguments.
+    #[arg(long, value_name = "HEX|PATH")]
+    pub init_code: String,
+
+    /// Byte offsets of the init code that may be changed, e.g. an unused
+    /// constructor argument or trailing data byte, as a range like `100..132`
+    /// or `131..=131`.
+    #[arg(long, value_name = "RANGE")]
+    pub mutable: String,
+
+    /// Address of the contract that will be calling CREATE2.
+    #[arg(long, value_name = "ADDRESS")]
+    pub factory: String,
+
+    /// The fixed 32-byte salt that the factory deploys the contract with.
+    #[arg(long, value_name = "SALT")]
+    pub salt: String,
+
+    /// Score the address of the contract that the contract deploys via CREATE
+    /// with this nonce, rather than that of the contract itself.
+    #[arg(long, value_name = "NONCE", value_parser = clap::value_parser!(u64).range(1..))]
+    pub nonce: Option<u64>,
+
+    /// File that the patched init code is appended to [default:
+    /// mutated_init_code.txt].
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// OpenCL device to benchmark; the CPU is benchmarked when omitted.
+    #[arg(long, value_name = "DEVICE")]
+    pub gpu_device: Option<u8>,
+
+    /// How long to run the benchmark for.
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    pub seconds: u64,
+}
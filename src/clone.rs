@@ -0,0 +1,241 @@
+use crate::cli::CloneArgs;
+use crate::error::{Argument, ConfigError};
+use crate::parse;
+use alloy_primitives::hex;
+use serde::Deserialize;
+
+/// Length of the runtime code of an ERC-1167 minimal proxy, which any
+/// immutable args are appended to.
+const ERC1167_RUNTIME_LENGTH: usize = 0x2d;
+
+/// Most bytes of immutable args that fit alongside the proxy in a contract of
+/// the maximum size.
+const MAX_IMMUTABLE_ARGS_LENGTH: usize = 0x6000 - ERC1167_RUNTIME_LENGTH;
+
+/// A kind of minimal proxy clone, whose init code is built from the address of
+/// the implementation it delegates to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CloneKind {
+    /// The ERC-1167 minimal proxy deployed by OpenZeppelin's
+    /// `Clones.cloneDeterministic` (and, with immutable args, by
+    /// `Clones.cloneDeterministicWithImmutableArgs` and Solady's
+    /// `LibClone.cloneDeterministic`).
+    Erc1167,
+    /// The 44-byte minimal proxy deployed by Solady's
+    /// `LibClone.cloneDeterministic` without immutable args.
+    Solady,
+    /// The ERC-1967 proxy deployed by Solady's
+    /// `LibClone.deployDeterministicERC1967`.
+    Erc1967,
+}
+
+/// Requires the kind of minimal proxy clone, the implementation that it
+/// delegates to and any immutable args appended to its runtime code.
+pub struct CloneConfig {
+    pub kind: CloneKind,
+    pub implementation: [u8; 20],
+    pub immutable_args: Vec<u8>,
+}
+
+impl CloneConfig {
+    pub fn new(args: &CloneArgs) -> Result<Self, ConfigError> {
+        let Some(kind) = args.kind else {
+            return Err(ConfigError::Missing(Argument::Clone));
+        };
+        let Some(implementation) = &args.implementation else {
+            return Err(ConfigError::Requires(
+                Argument::Clone,
+                Argument::CloneImplementation,
+            ));
+        };
+        let implementation = parse::address(Argument::CloneImplementation, implementation)?;
+
+        let immutable_args = match &args.immutable_args {
+            Some(immutable_args) => parse::hex(Argument::CloneArgs, immutable_args)?,
+            None => vec![],
+        };
+        if !immutable_args.is_empty() && kind != CloneKind::Erc1167 {
+            return Err(ConfigError::InvalidCloneArgs(
+                "immutable args are only supported by erc1167 clones",
+            ));
+        }
+        if immutable_args.len() > MAX_IMMUTABLE_ARGS_LENGTH {
+            return Err(ConfigError::InvalidCloneArgs(
+                "the clone would exceed the contract size limit",
+            ));
+        }
+
+        Ok(Self {
+            kind,
+            implementation,
+            immutable_args,
+        })
+    }
+
+    /// Build the init code that deploys the clone.
+    pub fn init_code(&self) -> Vec<u8> {
+        match self.kind {
+            CloneKind::Erc1167 => erc1167_init_code(&self.implementation, &self.immutable_args),
+            CloneKind::Solady => solady_init_code(&self.implementation),
+            CloneKind::Erc1967 => erc1967_init_code(&self.implementation),
+        }
+    }
+}
+
+/// Build the init code of an ERC-1167 minimal proxy. Any immutable args are
+/// appended to its runtime code, which the creation code then copies along
+/// with them.
+fn erc1167_init_code(implementation: &[u8; 20], immutable_args: &[u8]) -> Vec<u8> {
+    let mut init_code = if immutable_args.is_empty() {
+        hex!("3d602d80600a3d3981f3").to_vec()
+    } else {
+        let runtime_length = (ERC1167_RUNTIME_LENGTH + immutable_args.len()) as u16;
+        let mut creation_code = vec![0x61];
+        creation_code.extend_from_slice(&runtime_length.to_be_bytes());
+        creation_code.extend_from_slice(&hex!("3d81600a3d39f3"));
+        creation_code
+    };
+    init_code.extend_from_slice(&hex!("363d3d373d3d3d363d73"));
+    init_code.extend_from_slice(implementation);
+    init_code.extend_from_slice(&hex!("5af43d82803e903d91602b57fd5bf3"));
+    init_code.extend_from_slice(immutable_args);
+    init_code
+}
+
+/// Build the init code of Solady's minimal proxy (as deployed by
+/// `LibClone.cloneDeterministic`), a byte shorter than ERC-1167's.
+fn solady_init_code(implementation: &[u8; 20]) -> Vec<u8> {
+    let mut init_code = hex!("602c3d8160093d39f33d3d3d3d363d3d37363d73").to_vec();
+    init_code.extend_from_slice(implementation);
+    init_code.extend_from_slice(&hex!("5af43d3d93803e602a57fd5bf3"));
+    init_code
+}
+
+/// Build the init code of Solady's ERC-1967 proxy (as deployed by
+/// `LibClone.deployDeterministicERC1967`), which stores the implementation in
+/// the ERC-1967 implementation slot and delegates every call to it.
+pub(crate) fn erc1967_init_code(implementation: &[u8; 20]) -> Vec<u8> {
+    let mut init_code = hex!("603d3d8160223d3973").to_vec();
+    init_code.extend_from_slice(implementation);
+    init_code.extend_from_slice(&hex!(
+        "60095155f3363d3d373d3d363d7f360894a13ba1a3210667c828492db98dca3e2076cc"
+        "3735a920a3ca505d382bbc545af43d6000803e6038573d6000fd5b3d6000f3"
+    ));
+    init_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::RunSettings;
+    use crate::{keccak256, Derivation};
+    use alloy_primitives::Address;
+
+    const IMPLEMENTATION: [u8; 20] = [0x11; 20];
+    const FACTORY: [u8; 20] = [0xfa; 20];
+    const SALT: [u8; 32] = [0x5a; 32];
+
+    /// The init code of the clone, its hash, and the address that `FACTORY`
+    /// deploys it to via CREATE2 with `SALT`, as deployed by running the init
+    /// code through such a factory on an EVM; each clone deployed there
+    /// delegated calls to `IMPLEMENTATION` with their calldata.
+    fn check(config: CloneConfig, init_code: &str, init_code_hash: &str, address: &str) {
+        let actual = config.init_code();
+        assert_eq!(hex::encode(&actual), init_code);
+        assert_eq!(hex::encode(keccak256(&actual)), init_code_hash);
+
+        let settings = RunSettings {
+            factory_address: FACTORY,
+            hashed_salt: None,
+            derivation: Derivation::Create2,
+            create2_prefix: 0xff,
+        };
+        assert_eq!(
+            settings.derive_address(&keccak256(&actual), &SALT),
+            address.parse::<Address>().unwrap()
+        );
+    }
+
+    fn clone(kind: CloneKind, immutable_args: &[u8]) -> CloneConfig {
+        CloneConfig {
+            kind,
+            implementation: IMPLEMENTATION,
+            immutable_args: immutable_args.to_vec(),
+        }
+    }
+
+    #[test]
+    fn erc1167_init_code() {
+        check(
+            clone(CloneKind::Erc1167, &[]),
+            "3d602d80600a3d3981f3363d3d373d3d3d363d73111111111111111111111111111111111111111\
+             15af43d82803e903d91602b57fd5bf3",
+            "a2d88143eeea48efcc9f4a249ebe53d688d02897963ffc34032b89239ec11b5e",
+            "0x738F187EeeE9d4D8AcFd77e2F9742ca23557375c",
+        );
+    }
+
+    #[test]
+    fn erc1167_init_code_with_immutable_args() {
+        // the creation code pushes the length of the runtime code and args
+        check(
+            clone(CloneKind::Erc1167, &hex!("deadbeefcafe")),
+            "6100333d81600a3d39f3363d3d373d3d3d363d7311111111111111111111111111111111111111\
+             115af43d82803e903d91602b57fd5bf3deadbeefcafe",
+            "d5bbf989bf7761d845eca08d991bd9a526065a71aeaa6b35e15a78a218342184",
+            "0xCe991912Cea442A82B6E97f8da7Af6F385660D94",
+        );
+    }
+
+    #[test]
+    fn solady_init_code() {
+        check(
+            clone(CloneKind::Solady, &[]),
+            "602c3d8160093d39f33d3d3d3d363d3d37363d73111111111111111111111111111111111111111\
+             15af43d3d93803e602a57fd5bf3",
+            "4f83b61c54ed45c71d48b141aacb77f249501a6ee69d07f5ea1541348e30be2f",
+            "0x33013b43250C250c0e89144eD3fDbea83D01aa52",
+        );
+    }
+
+    #[test]
+    fn erc1967_init_code() {
+        check(
+            clone(CloneKind::Erc1967, &[]),
+            "603d3d8160223d3973111111111111111111111111111111111111111160095155f3363d3d373d\
+             3d363d7f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc545af43d\
+             6000803e6038573d6000fd5b3d6000f3",
+            "ed831a55dd0e6304ef245908b9e22e8918c31c9554203043c5985fbb89dc0df5",
+            "0x387A73855cda0fEAaF534971fE713fe34e620cad",
+        );
+
+        // the implementation is stored in keccak256("eip1967.proxy.implementation") - 1
+        let mut slot = keccak256(b"eip1967.proxy.implementation");
+        slot[31] -= 1;
+        let init_code = clone(CloneKind::Erc1967, &[]).init_code();
+        assert!(init_code.windows(32).any(|window| window == slot));
+    }
+
+    #[test]
+    fn immutable_args_fill_the_contract_size_limit() {
+        let largest = clone(CloneKind::Erc1167, &vec![0xab; MAX_IMMUTABLE_ARGS_LENGTH]);
+        assert_eq!(largest.init_code()[..3], [0x61, 0x60, 0x00]);
+        assert_eq!(largest.init_code().len(), 10 + 0x6000);
+    }
+
+    #[test]
+    fn rejects_invalid_immutable_args() {
+        let args = |kind, immutable_args: &str| CloneArgs {
+            kind: Some(kind),
+            implementation: Some(format!("0x{}", hex::encode(IMPLEMENTATION))),
+            immutable_args: Some(immutable_args.to_string()),
+        };
+        assert!(CloneConfig::new(&args(CloneKind::Erc1167, "0xdeadbeef")).is_ok());
+        assert!(CloneConfig::new(&args(CloneKind::Solady, "0xdeadbeef")).is_err());
+        assert!(CloneConfig::new(&args(CloneKind::Erc1967, "0xdeadbeef")).is_err());
+
+        let too_long = "ab".repeat(MAX_IMMUTABLE_ARGS_LENGTH + 1);
+        assert!(CloneConfig::new(&args(CloneKind::Erc1167, &too_long)).is_err());
+    }
+}
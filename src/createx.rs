@@ -0,0 +1,322 @@
+use crate::salt::{SaltHash, SaltLayout};
+use crate::ConfigError;
+use serde::Deserialize;
+use std::fmt;
+
+/// The canonical CreateX deployment, at the same address on every chain.
+pub(crate) const CREATEX_FACTORY: &str = "0xba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed";
+
+/// How CreateX's `_guard` protects a salt, selected by its first 21 bytes:
+/// the first 20 bytes are `msg.sender` (permissioned deploy protection) or
+/// zero, and the 21st byte is `0x01` (cross-chain redeploy protection) or
+/// `0x00`. The guarded salt is then used for CREATE2 or CREATE3 in place of
+/// the salt passed to `deployCreate2` or `deployCreate3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CreatexGuard {
+    /// Only the caller can deploy with the salt, on any chain:
+    /// `keccak256(abi.encode(msg.sender, salt))`.
+    Sender,
+    /// Only the caller can deploy with the salt, on the given chain only:
+    /// `keccak256(abi.encode(msg.sender, block.chainid, salt))`.
+    SenderChain,
+    /// Anyone can deploy with the salt, on the given chain only:
+    /// `keccak256(abi.encode(block.chainid, salt))`.
+    Chain,
+    /// Anyone can deploy with the salt, on any chain:
+    /// `keccak256(abi.encode(salt))`.
+    None,
+}
+
+impl CreatexGuard {
+    /// Whether the salt is bound to the caller of CreateX.
+    pub fn is_permissioned(self) -> bool {
+        matches!(self, CreatexGuard::Sender | CreatexGuard::SenderChain)
+    }
+
+    /// Whether the salt is bound to the chain it is deployed on.
+    pub fn is_cross_chain_protected(self) -> bool {
+        matches!(self, CreatexGuard::SenderChain | CreatexGuard::Chain)
+    }
+
+    /// The guard that CreateX selects for the given salt when called by the
+    /// given caller, or `None` if it reverts with `InvalidSalt` because the
+    /// 21st byte is neither `0x00` nor `0x01`. Salts beginning with any other
+    /// address are hashed without protection, like those beginning with zero.
+    pub(crate) fn of_salt(salt: &[u8; 32], calling_address: &[u8; 20]) -> Option<Self> {
+        let sender = &salt[..20];
+        let redeploy_protection = salt[20];
+
+        if calling_address != &[0; 20] && sender == calling_address {
+            match redeploy_protection {
+                0x00 => Some(CreatexGuard::Sender),
+                0x01 => Some(CreatexGuard::SenderChain),
+                _ => None,
+            }
+        } else if sender == [0; 20] {
+            match redeploy_protection {
+                0x00 => Some(CreatexGuard::None),
+                0x01 => Some(CreatexGuard::Chain),
+                _ => None,
+            }
+        } else {
+            Some(CreatexGuard::None)
+        }
+    }
+
+    /// The layout of the salts passed to CreateX that select this guard, with
+    /// the 8-byte counter the GPU requires or the 6-byte one the CPU steps
+    /// through.
+    pub(crate) fn salt_layout(
+        self,
+        calling_address: &[u8; 20],
+        gpu: bool,
+    ) -> Result<SaltLayout, ConfigError> {
+        let prefix = match self {
+            CreatexGuard::Sender => "caller,fixed:0x00",
+            CreatexGuard::SenderChain => "caller,fixed:0x01",
+            CreatexGuard::Chain => "zero:20,fixed:0x01",
+            CreatexGuard::None => "zero:20,fixed:0x00",
+        };
+        let rest = if gpu {
+            "random:3,counter:8"
+        } else {
+            "random:5,counter:6"
+        };
+        SaltLayout::parse(&format!("{prefix},{rest}"), calling_address)
+    }
+
+    /// The hash that `_guard` derives the CREATE2 (or CREATE3) salt with,
+    /// where every value is ABI-encoded as a 32-byte word ahead of the salt.
+    pub(crate) fn hashed_salt(self, calling_address: &[u8; 20], chain_id: u64) -> SaltHash {
+        let mut prefix = vec![];
+        if self.is_permissioned() {
+            prefix.extend_from_slice(&[0; 12]);
+            prefix.extend_from_slice(calling_address);
+        }
+        if self.is_cross_chain_protected() {
+            prefix.extend_from_slice(&[0; 24]);
+            prefix.extend_from_slice(&chain_id.to_be_bytes());
+        }
+        SaltHash::new(prefix, vec![])
+    }
+}
+
+impl fmt::Display for CreatexGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CreatexGuard::Sender => "permissioned deploy protection",
+            CreatexGuard::SenderChain => {
+                "permissioned deploy protection and cross-chain redeploy protection"
+            }
+            CreatexGuard::Chain => "cross-chain redeploy protection",
+            CreatexGuard::None => "no protection",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factory::FactoryPreset;
+    use crate::tests::config;
+    use alloy_primitives::{hex, Address};
+    use clap::ValueEnum;
+
+    const CALLER: [u8; 20] = [0x11; 20];
+    const CHAIN_ID: u64 = 10;
+
+    /// Init code of a contract returning 42, whose hash the vectors below use.
+    const INIT_CODE: &str = "0x600a600c600039600a6000f3602a60005260206000f3";
+
+    /// Salts beginning with the caller, zero and another address, each with
+    /// the given 21st byte, and for those that CreateX doesn't revert on, the
+    /// guarded salt and the addresses that `deployCreate2` and `deployCreate3`
+    /// deploy to on chain 10 when called by the caller, computed by an
+    /// independent port of `_parseSalt` and `_guard`.
+    #[allow(clippy::type_complexity)]
+    const VECTORS: [([u8; 20], u8, Option<(CreatexGuard, &str, &str, &str)>); 9] = [
+        (
+            CALLER,
+            0x00,
+            Some((
+                CreatexGuard::Sender,
+                "b54aed8349f4c725178953611c9c6bb51cf2bf93d4f0d24bf8ec117712671444",
+                "0x34D7605df229682C3607c664DD9214e3c26761ce",
+                "0x71D15bd1C9ACf7B1EC3eDe3E976d591e8eeC2E38",
+            )),
+        ),
+        (
+            CALLER,
+            0x01,
+            Some((
+                CreatexGuard::SenderChain,
+                "988ccf64c0e44d7a7adf0c48e81414da4e6baab21cc8c6b7623f92bb9eb5f299",
+                "0xF2614F074905e01767E8Bf579e94f03A1a0cfE19",
+                "0xC41473fC1Ed2065A5fa80621c67B679c650D1fCD",
+            )),
+        ),
+        (CALLER, 0x02, None),
+        (
+            [0; 20],
+            0x00,
+            Some((
+                CreatexGuard::None,
+                "afc5d53129d2a94fbff4e88fd1d4df7171cb2619afbbc601ebbde245c9341c3d",
+                "0x9c8188D5f343cca6E26da77641d62C4AF8c9597c",
+                "0xAd3Fa0E20f831ECbb8465f1644989B08CB0D6c08",
+            )),
+        ),
+        (
+            [0; 20],
+            0x01,
+            Some((
+                CreatexGuard::Chain,
+                "e8410b3b0e74632749b2abbfc32cb2be299f857389a7fc16f0e0fa2cbb0fb2ff",
+                "0xAb276552A674aa262aE50D645E610acb713ef525",
+                "0x35AC681ba74D1d829D8CFfa40128879E31f1f7AC",
+            )),
+        ),
+        ([0; 20], 0x02, None),
+        // a pseudo-random prefix is hashed without protection, even with the
+        // cross-chain redeploy protection flag, and never reverts
+        (
+            [0x22; 20],
+            0x00,
+            Some((
+                CreatexGuard::None,
+                "82566cf8534d1c2bb85f5feffe57ab3a9308a7ee25e5018420dded2516e6e43c",
+                "0x9d957B8A3a4237Db8c856A3db7A40Eac9F64E24C",
+                "0x4e0b8Ef86d3481AF30530BE4dc7B6B496507876e",
+            )),
+        ),
+        (
+            [0x22; 20],
+            0x01,
+            Some((
+                CreatexGuard::None,
+                "e41a5202532cff069fc426c7eaa75a43c4f0d6b88892dd0cae1c58a31ee882f3",
+                "0x7721dc84B5F6A9d929A937be57925FeA97BBd2Ce",
+                "0xfb62B5AB896707f204deB24f77173F7BAa3b7Ed6",
+            )),
+        ),
+        (
+            [0x22; 20],
+            0x07,
+            Some((
+                CreatexGuard::None,
+                "64eab914512fbb66ec518fca2eea241a30468facfd991a364c841a6cd12b1002",
+                "0x8451F129E11158c985952319F8D6F9bcca4cf782",
+                "0xdDDc2edD3835998983015c41DF9bD40dF5b35b88",
+            )),
+        ),
+    ];
+
+    fn salt(prefix: [u8; 20], flag: u8) -> [u8; 32] {
+        let mut salt = [0x5a; 32];
+        salt[..20].copy_from_slice(&prefix);
+        salt[20] = flag;
+        salt
+    }
+
+    #[test]
+    fn parses_salts_like_createx() {
+        for (prefix, flag, expected) in VECTORS {
+            let guard = CreatexGuard::of_salt(&salt(prefix, flag), &CALLER);
+            assert_eq!(guard, expected.map(|(guard, ..)| guard), "{flag}");
+        }
+    }
+
+    #[test]
+    fn guards_salts_like_createx() {
+        for (prefix, flag, expected) in VECTORS {
+            let Some((guard, guarded_salt, ..)) = expected else {
+                continue;
+            };
+            let hashed_salt = guard.hashed_salt(&CALLER, CHAIN_ID);
+            assert_eq!(
+                hex::encode(hashed_salt.hash(&salt(prefix, flag))),
+                guarded_salt
+            );
+        }
+    }
+
+    #[test]
+    fn derives_the_addresses_createx_deploys_to() {
+        let caller = format!("0x{}", hex::encode(CALLER));
+        let chain_id = CHAIN_ID.to_string();
+        for (prefix, flag, expected) in VECTORS {
+            let Some((guard, _, create2, create3)) = expected else {
+                continue;
+            };
+            let guard = guard.to_possible_value().unwrap();
+            let mut args = vec!["--createx", guard.get_name(), "--chain-id", &chain_id];
+            if prefix == CALLER {
+                args.extend(["--caller", &caller]);
+            }
+
+            let search = config(&[&args[..], &["--init-code", INIT_CODE]].concat()).unwrap();
+            let address = crate::derive_address(&search, &salt(prefix, flag));
+            assert_eq!(address, create2.parse::<Address>().unwrap());
+
+            let search = config(&[&args[..], &["--create3", "createx"]].concat()).unwrap();
+            let address = crate::derive_address(&search, &salt(prefix, flag));
+            assert_eq!(address, create3.parse::<Address>().unwrap());
+        }
+    }
+
+    #[test]
+    fn searches_salts_that_select_the_guard() {
+        let caller = format!("0x{}", hex::encode(CALLER));
+        for (guard, prefix, flag) in [
+            (CreatexGuard::Sender, CALLER, 0x00),
+            (CreatexGuard::SenderChain, CALLER, 0x01),
+            (CreatexGuard::Chain, [0; 20], 0x01),
+            (CreatexGuard::None, [0; 20], 0x00),
+        ] {
+            let name = guard.to_possible_value().unwrap();
+            let mut args = vec!["--createx", name.get_name(), "--init-code", INIT_CODE];
+            if guard.is_permissioned() {
+                args.extend(["--caller", &caller]);
+            }
+            if guard.is_cross_chain_protected() {
+                args.extend(["--chain-id", "10"]);
+            }
+            let search = config(&args).unwrap();
+            assert_eq!(
+                search.factory_address,
+                hex!("ba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed")
+            );
+
+            // the salt printed is the one passed to CreateX, which selects the guard
+            let salt = search.salt_layout.salt(&[0xab; 5], 0x0102030405);
+            assert_eq!(salt[..21], [&prefix[..], &[flag]].concat());
+            assert_eq!(CreatexGuard::of_salt(&salt, &CALLER), Some(guard));
+        }
+
+        // cross-chain redeploy protection needs the chain id
+        let args = ["--createx", "chain", "--init-code", INIT_CODE];
+        assert!(matches!(
+            config(&args),
+            Err(ConfigError::Requires(_, crate::Argument::ChainId))
+        ));
+    }
+
+    #[test]
+    fn encodes_deploy_calldata_with_the_salt_as_found() {
+        let salt = salt(CALLER, 0x01);
+        let init_code = hex::decode(INIT_CODE).unwrap();
+        let args = "1111111111111111111111111111111111111111015a5a5a5a5a5a5a5a5a5a5a\
+                    0000000000000000000000000000000000000000000000000000000000000040\
+                    0000000000000000000000000000000000000000000000000000000000000016\
+                    600a600c600039600a6000f3602a60005260206000f300000000000000000000";
+        assert_eq!(
+            hex::encode(FactoryPreset::Createx.calldata(false, &salt, &init_code)),
+            format!("26307668{args}")
+        );
+        assert_eq!(
+            hex::encode(FactoryPreset::Createx.calldata(true, &salt, &init_code)),
+            format!("9c36a286{args}")
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use crate::children::zero_bytes;
+use crate::cli::{DeployerArgs, PlanArgs};
+use crate::derivation::create_address;
+use crate::error::{Argument, ConfigError};
+use crate::{parse, Reward, Scorer};
+use alloy_primitives::{hex, Address};
+use fs4::FileExt;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::SecretKey;
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::prelude::*;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Number of keys generated in each parallel batch.
+const KEY_BATCH_SIZE: usize = 0x10000;
+
+/// Default file that the private keys of found deployers are appended to.
+const DEFAULT_KEYS_OUTPUT: &str = "deployer_keys.txt";
+
+/// Requires the nonces of the deployer accounts to check and the file that
+/// the private keys of found deployers are appended to. The nonces are given
+/// as a single nonce (`0`, the default, for the first deployment of a fresh
+/// account) or as a range (`0..4`, or `0..=3`). Addresses are scored by
+/// `Reward` unless another `Scorer` is set.
+pub struct DeployerConfig {
+    pub nonces: Range<u64>,
+    pub keys_output: PathBuf,
+    pub scorer: Arc<dyn Scorer>,
+}
+
+impl DeployerConfig {
+    pub fn new(args: &DeployerArgs) -> Result<Self, ConfigError> {
+        let nonces = parse_nonces(&args.nonces)?;
+
+        Ok(Self {
+            nonces,
+            keys_output: args
+                .keys_output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_KEYS_OUTPUT)),
+            scorer: Arc::new(Reward::new()),
+        })
+    }
+}
+
+/// Requires an existing deployer account and the range of its upcoming nonces
+/// to scan, starting from its current nonce. Addresses are scored by `Reward`
+/// unless another `Scorer` is set.
+pub struct PlanConfig {
+    pub deployer: [u8; 20],
+    pub nonces: Range<u64>,
+    pub scorer: Arc<dyn Scorer>,
+}
+
+impl PlanConfig {
+    pub fn new(args: &PlanArgs) -> Result<Self, ConfigError> {
+        let deployer = parse::address(Argument::Deployer, &args.deployer)?;
+
+        Ok(Self {
+            deployer,
+            nonces: args.nonce..args.nonce.saturating_add(args.count),
+            scorer: Arc::new(Reward::new()),
+        })
+    }
+}
+
+fn parse_nonces(value: &str) -> Result<Range<u64>, ConfigError> {
+    let invalid = ConfigError::InvalidNonces;
+    let number = |value: &str| {
+        value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| invalid("expected a nonce like `0` or a range like `0..4`"))
+    };
+
+    let nonces = if let Some((start, end)) = value.split_once("..=") {
+        number(start)?..number(end)?.saturating_add(1)
+    } else if let Some((start, end)) = value.split_once("..") {
+        number(start)?..number(end)?
+    } else {
+        let nonce = number(value)?;
+        nonce..nonce.saturating_add(1)
+    };
+
+    if nonces.is_empty() {
+        return Err(invalid("the range of nonces is empty"));
+    }
+    Ok(nonces)
+}
+
+/// Given a DeployerConfig object, search for private keys of fresh accounts
+/// that will deploy a contract to a gas-efficient address via CREATE, with a
+/// nonce in the configured range. The address of a contract deployed via CREATE
+/// depends only on the deployer and its nonce, so each random key is checked
+/// against every nonce in the range.
+///
+/// When a key is found, its private key will be appended to the keys file (by
+/// default `deployer_keys.txt`, readable only by its owner on Unix) along with
+/// the deployer address, the nonce, the resultant address and the "value"
+/// (i.e. approximate rarity) of the resultant address. Private keys are never
+/// written to the terminal or to the output file of the CREATE2 search.
+pub fn deployer(config: DeployerConfig) -> Result<(), Box<dyn Error>> {
+    println!(
+        "Searching on the CPU for deployer keys with nonces {}..{}...",
+        config.nonces.start, config.nonces.end
+    );
+
+    // (create if necessary) and open a file where found keys will be written
+    let file = keys_file(&config.keys_output)?;
+
+    // begin searching for keys
+    loop {
+        (0..KEY_BATCH_SIZE)
+            .into_par_iter() // parallelization
+            .for_each(|_| {
+                // generate a random private key (retrying the vanishingly rare
+                // values that are out of range) and derive its address
+                let secret_key = loop {
+                    if let Ok(key) = SecretKey::from_slice(&thread_rng().gen::<[u8; 32]>()) {
+                        break key;
+                    }
+                };
+                let public_key = secret_key.public_key().to_encoded_point(false);
+                let deployer = Address::from_raw_public_key(&public_key.as_bytes()[1..]);
+
+                for nonce in config.nonces.clone() {
+                    let address = create_address(deployer.as_slice(), nonce);
+
+                    // score the address, only proceeding if an efficient
+                    // address has been found
+                    let Some(score) = config.scorer.score(&address) else {
+                        continue;
+                    };
+                    let reward = score.label;
+
+                    // display the deployer and the address, but not the key
+                    println!("{deployer} (nonce {nonce}) => {address} => {reward}");
+
+                    let output = format!(
+                        "0x{} => {deployer} => {nonce} => {address} => {reward}",
+                        hex::encode(secret_key.to_bytes())
+                    );
+
+                    // create a lock on the file before writing
+                    file.lock_exclusive().expect("Couldn't lock file.");
+
+                    // write the result to file
+                    writeln!(&file, "{output}").unwrap_or_else(|_| {
+                        panic!("Couldn't write to `{}` file.", config.keys_output.display())
+                    });
+
+                    // release the file lock
+                    FileExt::unlock(&file).expect("Couldn't unlock file.");
+                }
+            });
+    }
+}
+
+/// Open the file that private keys are appended to, restricting it to its
+/// owner on Unix.
+fn keys_file(path: &Path) -> Result<File, Box<dyn Error>> {
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+        let file = options
+            .open(path)
+            .map_err(|e| format!("could not open `{}` file: {e}", path.display()))?;
+
+        // the mode only applies to new files, so restrict existing ones too
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+
+    #[cfg(not(unix))]
+    {
+        Ok(options
+            .open(path)
+            .map_err(|e| format!("could not open `{}` file: {e}", path.display()))?)
+    }
+}
+
+/// Given a PlanConfig object, scan the upcoming nonces of an existing deployer
+/// account for contracts that would be deployed via CREATE to a gas-efficient
+/// address. Each such nonce is listed along with the number of transactions
+/// that must be sent first to reach it (e.g. by sending ETH to itself), the
+/// resultant address and the "value" (i.e. approximate rarity) of the
+/// resultant address.
+pub fn plan(config: PlanConfig) {
+    let deployer = Address::from(config.deployer);
+    println!(
+        "Scanning nonces {}..{} of {deployer}...",
+        config.nonces.start, config.nonces.end
+    );
+
+    let mut found = 0;
+    for nonce in config.nonces.clone() {
+        let address = create_address(&config.deployer, nonce);
+
+        // score the address, only proceeding if an efficient address has been
+        // found
+        let Some(score) = config.scorer.score(&address) else {
+            continue;
+        };
+        let reward = score.label;
+
+        // count total and leading zero bytes
+        let (leading, total) = zero_bytes(&address);
+
+        let burn = nonce - config.nonces.start;
+        println!(
+            "nonce {nonce} (burn {burn} transactions first) => {address} => {reward} \
+             ({leading} / {total})"
+        );
+        found += 1;
+    }
+
+    println!(
+        "{found} of {} nonces give an efficient address",
+        config.nonces.end - config.nonces.start
+    );
+}
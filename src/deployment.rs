@@ -0,0 +1,118 @@
+use crate::derivation::Derivation;
+use crate::error::{Argument, ConfigError};
+use crate::salt::{SaltHash, SaltLayout};
+use crate::scorer;
+use crate::{keccak256, parse, Config, CONTROL_CHARACTER};
+use alloy_primitives::{hex, Address};
+use std::fmt::Write as _;
+
+/// Another deployment of the contract that each salt must also give an
+/// efficient address for, e.g. on a chain where the factory or the compiled
+/// init code differs. Written as `FACTORY,CALLER,INIT_CODE_HASH`, optionally
+/// followed by how the address is derived (`create2`, `create3` or `zksync`,
+/// by default the same as the search itself).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deployment {
+    pub factory_address: [u8; 20],
+    pub calling_address: [u8; 20],
+    pub init_code_hash: [u8; 32],
+    pub derivation: Derivation,
+}
+
+impl Deployment {
+    /// Parse a deployment, taking the given derivation when none is named and
+    /// the given zkSync derivation (with the constructor input of the search)
+    /// for `zksync`.
+    pub(crate) fn parse(
+        value: &str,
+        derivation: Derivation,
+        zksync: Derivation,
+    ) -> Result<Self, ConfigError> {
+        let invalid = ConfigError::InvalidDeployment;
+
+        let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+        let (factory, caller, init_code_hash, derivation) = match fields[..] {
+            [factory, caller, init_code_hash] => (factory, caller, init_code_hash, derivation),
+            [factory, caller, init_code_hash, named] => {
+                let derivation = match named {
+                    "create2" => Derivation::Create2,
+                    "create3" => Derivation::Create3,
+                    "zksync" => zksync,
+                    _ => {
+                        return Err(invalid(
+                            "unknown derivation (expected create2, create3 or zksync)",
+                        ))
+                    }
+                };
+                (factory, caller, init_code_hash, derivation)
+            }
+            _ => {
+                return Err(invalid(
+                    "expected `FACTORY,CALLER,INIT_CODE_HASH[,DERIVATION]`",
+                ))
+            }
+        };
+
+        Ok(Self {
+            factory_address: parse::address(Argument::Deployment, factory)?,
+            calling_address: parse::address(Argument::Deployment, caller)?,
+            init_code_hash: parse::fixed(Argument::Deployment, init_code_hash)?,
+            derivation,
+        })
+    }
+
+    /// The salt of this deployment: the salt found by the search, beginning
+    /// with this deployment's caller in place of the caller of the search if
+    /// the salt is bound to it.
+    pub(crate) fn salt(
+        &self,
+        salt: &[u8; 32],
+        salt_layout: &SaltLayout,
+        calling_address: &[u8; 20],
+    ) -> [u8; 32] {
+        salt_layout.rebind(salt, calling_address, &self.calling_address)
+    }
+
+    /// Derive the address of this deployment from its salt, hashed the same
+    /// way as the salts of the search.
+    pub(crate) fn address(&self, salt: &[u8; 32], hashed_salt: Option<&SaltHash>) -> Address {
+        let mut preimage = self
+            .derivation
+            .preimage_header(CONTROL_CHARACTER, &self.factory_address);
+        match hashed_salt {
+            Some(hashed_salt) => preimage.extend_from_slice(&hashed_salt.hash(salt)),
+            None => preimage.extend_from_slice(salt),
+        }
+        preimage.extend_from_slice(&self.init_code_hash);
+        preimage.extend_from_slice(self.derivation.preimage_footer());
+
+        self.derivation.address(&keccak256(&preimage)[12..])
+    }
+}
+
+/// Derive the address of every other deployment from the salt found by the
+/// search, returning `None` if any of them misses the thresholds, and
+/// otherwise each deployment's number (from 1, in the order given), salt
+/// (which begins with its own caller if the salt is bound to it), address and
+/// score as appended to the result, e.g.
+/// ` | deployment 1 => 0x<salt> => 0x... => 4 | deployment 2 => 0x<salt> => 0x... => 0`.
+pub(crate) fn describe(config: &Config, salt: &[u8; 32]) -> Option<String> {
+    let mut description = String::new();
+    for (i, deployment) in config.deployments.iter().enumerate() {
+        let salt = deployment.salt(salt, &config.salt_layout, &config.calling_address);
+        let address = deployment.address(&salt, config.hashed_salt.as_ref());
+        if !config.scorer.reaches(&address, config.threshold()) {
+            return None;
+        }
+
+        let score = scorer::label(config.scorer.as_ref(), &address);
+        write!(
+            description,
+            " | deployment {} => 0x{} => {address} => {score}",
+            i + 1,
+            hex::encode(salt)
+        )
+        .unwrap();
+    }
+    Some(description)
+}
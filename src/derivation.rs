@@ -0,0 +1,212 @@
+use crate::error::{Argument, ConfigError};
+use crate::{keccak256, parse, zksync};
+use alloy_primitives::Address;
+use std::fmt;
+
+/// Init code hash of the minimal CREATE3 proxy, `0x67363d3d37363d34f03d5260086018f3`,
+/// which deploys whatever init code it is called with via CREATE.
+const CREATE3_PROXY_INIT_CODE_HASH: &str =
+    "0x21c35dbe1b344a2488cf3321d6ce542f8e9f305544ff09e4993a62319a497c1f";
+
+/// Named CREATE3 implementations and the init code hash of the proxy that each
+/// deploys via CREATE2. They all share the same minimal proxy.
+pub const CREATE3_PRESETS: &[(&str, &str)] = &[
+    ("solady", CREATE3_PROXY_INIT_CODE_HASH),
+    ("solmate", CREATE3_PROXY_INIT_CODE_HASH),
+    ("sequence", CREATE3_PROXY_INIT_CODE_HASH),
+    ("createx", CREATE3_PROXY_INIT_CODE_HASH),
+];
+
+/// How the address of the deployed contract is derived from the salt: how the
+/// CREATE2 preimage is built around the salt, and how the address is derived
+/// from the address that CREATE2 computes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derivation {
+    /// The factory deploys the contract via CREATE2 directly.
+    Create2,
+    /// The factory deploys a proxy via CREATE2 (so the init code hash is that
+    /// of the proxy rather than the contract), which in turn deploys the
+    /// contract via CREATE with a nonce of 1.
+    Create3,
+    /// The factory deploys the contract on zkSync Era, whose CREATE2 preimage
+    /// is `keccak256("zksyncCreate2") ++ sender ++ salt ++ bytecodeHash ++
+    /// keccak256(constructorInput)` (with the sender padded to 32 bytes, and
+    /// the versioned bytecode hash in place of the init code hash).
+    ZkSync { constructor_input_hash: [u8; 32] },
+}
+
+impl Derivation {
+    /// The part of the CREATE2 preimage that precedes the salt, beginning with
+    /// the given prefix byte (`0xff`, or e.g. `0x41` on Tron) unless the
+    /// preimage has a prefix of its own.
+    pub fn preimage_header(self, prefix: u8, factory: &[u8; 20]) -> Vec<u8> {
+        let mut header = match self {
+            Derivation::Create2 | Derivation::Create3 => vec![prefix],
+            Derivation::ZkSync { .. } => {
+                let mut header = zksync::create2_prefix().to_vec();
+                header.extend_from_slice(&[0; 12]);
+                header
+            }
+        };
+        header.extend_from_slice(factory);
+        header
+    }
+
+    /// The part of the CREATE2 preimage that follows the init code hash.
+    pub fn preimage_footer(&self) -> &[u8] {
+        match self {
+            Derivation::Create2 | Derivation::Create3 => &[],
+            Derivation::ZkSync {
+                constructor_input_hash,
+            } => constructor_input_hash,
+        }
+    }
+
+    /// Derive the address of the deployed contract from the CREATE2 address.
+    pub fn address(self, create2_address: &[u8]) -> Address {
+        match self {
+            Derivation::Create2 | Derivation::ZkSync { .. } => Address::from_slice(create2_address),
+            Derivation::Create3 => create_address(create2_address, 1),
+        }
+    }
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Derivation::Create2 => "create2",
+            Derivation::Create3 => "create3",
+            Derivation::ZkSync { .. } => "zksync",
+        })
+    }
+}
+
+/// Resolve a `--create3` argument, either the name of a preset or the
+/// hex-encoded init code hash of the proxy, to the proxy's init code hash.
+pub(crate) fn create3_proxy_init_code_hash(value: &str) -> Result<[u8; 32], ConfigError> {
+    let hash = CREATE3_PRESETS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value.trim()))
+        .map_or(value, |(_, hash)| hash);
+
+    parse::fixed(Argument::Create3, hash)
+}
+
+/// Compute the address of the contract deployed via CREATE by the given sender
+/// with the given nonce, i.e. `keccak256(rlp([sender, nonce]))[12..]`.
+pub(crate) fn create_address(sender: &[u8], nonce: u64) -> Address {
+    Address::from_slice(&keccak256(&create_preimage(sender, nonce))[12..])
+}
+
+/// The RLP encoding of the sender and nonce, `rlp([sender, nonce])`.
+fn create_preimage(sender: &[u8], nonce: u64) -> Vec<u8> {
+    // the nonce is encoded as a single byte up to 0x7f (with zero as the empty
+    // string 0x80), and as a length-prefixed big-endian string beyond that
+    let nonce_bytes = nonce.to_be_bytes();
+    let nonce_bytes = &nonce_bytes[nonce.leading_zeros() as usize / 8..];
+
+    let mut rlp = Vec::with_capacity(31);
+    rlp.push(0);
+    rlp.push(0x94);
+    rlp.extend_from_slice(sender);
+    match nonce {
+        0 => rlp.push(0x80),
+        1..=0x7f => rlp.push(nonce as u8),
+        _ => {
+            rlp.push(0x80 + nonce_bytes.len() as u8);
+            rlp.extend_from_slice(nonce_bytes);
+        }
+    }
+    // the list prefix: both items total well under 56 bytes
+    rlp[0] = 0xc0 + (rlp.len() - 1) as u8;
+
+    rlp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::RunSettings;
+    use alloy_primitives::hex;
+
+    const DEPLOYER: [u8; 20] = hex!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+
+    #[test]
+    fn encodes_nonces_as_rlp() {
+        // (nonce, rlp([sender, nonce]) after the sender, address)
+        let vectors: [(u64, &str, &str); 8] = [
+            (0, "80", "0xcd234A471b72ba2F1Ccf0A70FCABA648a5eeCD8d"),
+            (1, "01", "0x343c43A37D37dfF08AE8C4A11544c718AbB4fCF8"),
+            (0x7f, "7f", "0x06d9a77f5E4b311Bae8D559DB9CDB4dF94104aA0"),
+            (0x80, "8180", "0x08e190dcB7b73F5fcDAbb43e102215c83659A76D"),
+            (0xff, "81ff", "0x3eF7c1a519E4b4431E317d7839340E3139B03c65"),
+            (
+                0x100,
+                "820100",
+                "0x3837C1Ae70354f670550C746580199Ac6a73Cb0a",
+            ),
+            (
+                0xffffffff,
+                "84ffffffff",
+                "0x4c9958390A81acc68A5f19AA8e6188bEbbbEeFD7",
+            ),
+            (
+                u64::MAX,
+                "88ffffffffffffffff",
+                "0x9bc924993b60399DF164c3763a964301D3dB95Ca",
+            ),
+        ];
+        for (nonce, encoded_nonce, address) in vectors {
+            let items = format!("94{}{encoded_nonce}", hex::encode(DEPLOYER));
+            let list = format!("{:02x}{items}", 0xc0 + items.len() / 2);
+            assert_eq!(
+                hex::encode(create_preimage(&DEPLOYER, nonce)),
+                list,
+                "{nonce}"
+            );
+            assert_eq!(
+                create_address(&DEPLOYER, nonce),
+                address.parse::<Address>().unwrap(),
+                "{nonce}"
+            );
+        }
+    }
+
+    #[test]
+    fn create3_proxy_is_the_minimal_proxy() {
+        let hash = keccak256(&hex!("67363d3d37363d34f03d5260086018f3"));
+        for (name, _) in CREATE3_PRESETS {
+            assert_eq!(create3_proxy_init_code_hash(name).unwrap(), hash);
+        }
+        assert_eq!(
+            create3_proxy_init_code_hash(&hex::encode(hash)).unwrap(),
+            hash
+        );
+        assert!(create3_proxy_init_code_hash("unknown").is_err());
+    }
+
+    #[test]
+    fn create3_deploys_via_the_proxy_at_nonce_1() {
+        // the proxy is deployed via CREATE2 with the salt, and the contract by
+        // the proxy via CREATE with a nonce of 1
+        let settings = RunSettings {
+            factory_address: hex!("0000000000ffe8b47b3e2130213b802212439497"),
+            hashed_salt: None,
+            derivation: Derivation::Create3,
+            create2_prefix: 0xff,
+        };
+        let proxy_init_code_hash = create3_proxy_init_code_hash("solady").unwrap();
+        let expected: Address = "0xCe191107313aAB5d9DBf5663cDFbc2F8FF4C9E36"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            settings.derive_address(&proxy_init_code_hash, &[0x5a; 32]),
+            expected
+        );
+
+        let proxy: Address = "0x48Ef962b983B0F4Ba5eeec9CF721bed76b220431"
+            .parse()
+            .unwrap();
+        assert_eq!(create_address(proxy.as_slice(), 1), expected);
+    }
+}
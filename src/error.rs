@@ -0,0 +1,295 @@
+use crate::factory::FactoryPreset;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// An argument that `Config::new` (or `verify`) validates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Argument {
+    Factory,
+    Caller,
+    InitCodeHash,
+    InitCode,
+    Artifact,
+    Create3,
+    ConstructorSig,
+    ConstructorArgs,
+    SaltLayout,
+    UnboundSalt,
+    HashedSalt,
+    LeadingZeroes,
+    TotalZeroes,
+    Salt,
+    Deployer,
+    Safe,
+    SafeProxyCode,
+    SafeSingleton,
+    SafeInitializer,
+    SafeOwners,
+    SafeFallbackHandler,
+    Account,
+    AccountImplementation,
+    AccountOwner,
+    AccountProxyCode,
+    AccountInitData,
+    Clone,
+    CloneImplementation,
+    CloneArgs,
+    Createx,
+    ChainId,
+    ZkSync,
+    Create2Prefix,
+    Tron,
+    Children,
+    Deployment,
+    FactoryPreset,
+    Target,
+    Until,
+}
+
+impl fmt::Display for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Argument::Factory => "--factory",
+            Argument::Caller => "--caller",
+            Argument::InitCodeHash => "--init-code-hash",
+            Argument::InitCode => "--init-code",
+            Argument::Artifact => "--artifact",
+            Argument::Create3 => "--create3",
+            Argument::ConstructorSig => "--constructor-sig",
+            Argument::ConstructorArgs => "--constructor-args",
+            Argument::SaltLayout => "--salt-layout",
+            Argument::UnboundSalt => "--unbound-salt",
+            Argument::HashedSalt => "--hashed-salt",
+            Argument::LeadingZeroes => "--leading-zeroes",
+            Argument::TotalZeroes => "--total-zeroes",
+            Argument::Salt => "--salt",
+            Argument::Deployer => "--deployer",
+            Argument::Safe => "--safe",
+            Argument::SafeProxyCode => "--safe-proxy-code",
+            Argument::SafeSingleton => "--safe-singleton",
+            Argument::SafeInitializer => "--safe-initializer",
+            Argument::SafeOwners => "--safe-owners",
+            Argument::SafeFallbackHandler => "--safe-fallback-handler",
+            Argument::Account => "--account",
+            Argument::AccountImplementation => "--account-implementation",
+            Argument::AccountOwner => "--account-owner",
+            Argument::AccountProxyCode => "--account-proxy-code",
+            Argument::AccountInitData => "--account-init-data",
+            Argument::Clone => "--clone",
+            Argument::CloneImplementation => "--clone-implementation",
+            Argument::CloneArgs => "--clone-args",
+            Argument::Createx => "--createx",
+            Argument::ChainId => "--chain-id",
+            Argument::ZkSync => "--zksync",
+            Argument::Create2Prefix => "--create2-prefix",
+            Argument::Tron => "--tron",
+            Argument::Children => "--children",
+            Argument::Deployment => "--deployment",
+            Argument::FactoryPreset => "--factory-preset",
+            Argument::Target => "--target",
+            Argument::Until => "--until",
+        })
+    }
+}
+
+/// The reason that an argument failed validation.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A required argument was not given.
+    Missing(Argument),
+    /// The first argument was given without the second, which it requires.
+    Requires(Argument, Argument),
+    /// The two arguments cannot be given together.
+    Conflicts(Argument, Argument),
+    /// The argument is not valid hex (an optional `0x` prefix is accepted).
+    InvalidHex(Argument),
+    /// The argument does not decode to the expected number of bytes.
+    InvalidLength {
+        argument: Argument,
+        expected: usize,
+        actual: usize,
+    },
+    /// The argument is not a valid Tron base58check address.
+    InvalidBase58(Argument),
+    /// The argument is a mixed-case address that does not match its EIP-55
+    /// checksum.
+    InvalidChecksum {
+        argument: Argument,
+        expected: String,
+    },
+    /// The argument selects a mode that the GPU search does not support.
+    GpuUnsupported(Argument),
+    /// The salt layout template is malformed or unsupported by the backend.
+    InvalidSaltLayout(&'static str),
+    /// The range of deployer nonces is malformed or empty.
+    InvalidNonces(&'static str),
+    /// The nonces or thresholds of the children are malformed.
+    InvalidChildren(&'static str),
+    /// The other deployment is not a factory, caller and init code hash.
+    InvalidDeployment(&'static str),
+    /// The range of mutable init code bytes is malformed, empty or extends
+    /// past the end of the init code.
+    InvalidMutableRange(&'static str),
+    /// The factory preset would revert on the salts of the salt layout.
+    SaltReverts(FactoryPreset, &'static str),
+    /// The target searched for in the same pass is malformed.
+    InvalidTarget(&'static str),
+    /// The quality at which the search is done is malformed.
+    InvalidUntil,
+    /// The preimage template for hashing the salt is malformed.
+    InvalidHashedSalt(&'static str),
+    /// The Safe threshold is zero or exceeds the number of owners.
+    InvalidSafeThreshold { threshold: u64, owners: usize },
+    /// The immutable args of the clone are unsupported or too long.
+    InvalidCloneArgs(&'static str),
+    /// The argument is outside of its valid range.
+    OutOfRange { argument: Argument, value: u8 },
+    /// The file named by the argument could not be read.
+    Read {
+        argument: Argument,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file or build artifact named by the argument holds no usable init
+    /// code.
+    InvalidInitCode {
+        argument: Argument,
+        reason: &'static str,
+    },
+    /// The build artifact contains unlinked library placeholders, listed here.
+    UnlinkedLibraries(Vec<String>),
+    /// The constructor signature could not be parsed.
+    InvalidConstructorSig(&'static str),
+    /// The number of constructor arguments does not match the signature.
+    ConstructorArgCount { expected: usize, actual: usize },
+    /// The constructor argument at the given (zero-based) index could not be
+    /// encoded as the type given for it in the signature.
+    InvalidConstructorArg { index: usize, reason: &'static str },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing(Argument::InitCodeHash) => f.write_str(
+                "didn't get an --init-code, --artifact, --init-code-hash, --create3 or --clone argument",
+            ),
+            ConfigError::Missing(argument) => write!(f, "didn't get a {argument} argument"),
+            ConfigError::Requires(argument, required) => {
+                write!(f, "{argument} requires a {required} argument")
+            }
+            ConfigError::Conflicts(argument, other) => {
+                write!(f, "{argument} cannot be used with a {other} argument")
+            }
+            ConfigError::InvalidHex(argument) => {
+                write!(f, "could not decode {argument} argument as hex")
+            }
+            ConfigError::InvalidBase58(argument) => {
+                write!(f, "could not decode {argument} argument as a Tron base58 address")
+            }
+            ConfigError::InvalidLength {
+                argument,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "invalid length for {argument} argument (expected {expected} bytes, got {actual})"
+            ),
+            ConfigError::InvalidChecksum { argument, expected } => write!(
+                f,
+                "{argument} argument does not match its EIP-55 checksum (expected {expected})"
+            ),
+            ConfigError::GpuUnsupported(argument) => write!(
+                f,
+                "{argument} is not supported by the GPU search (omit --gpu-device to use the CPU)"
+            ),
+            ConfigError::InvalidSaltLayout(reason) => {
+                write!(f, "invalid --salt-layout argument: {reason}")
+            }
+            ConfigError::InvalidHashedSalt(reason) => {
+                write!(f, "invalid --hashed-salt argument: {reason}")
+            }
+            ConfigError::InvalidSafeThreshold { threshold, owners } => write!(
+                f,
+                "invalid --safe-threshold argument {threshold} (must be between 1 and the {owners} \
+                 --safe-owners)"
+            ),
+            ConfigError::InvalidCloneArgs(reason) => {
+                write!(f, "invalid --clone-args argument: {reason}")
+            }
+            ConfigError::InvalidNonces(reason) => write!(f, "invalid --nonces argument: {reason}"),
+            ConfigError::InvalidChildren(reason) => {
+                write!(f, "invalid --children argument: {reason}")
+            }
+            ConfigError::InvalidDeployment(reason) => {
+                write!(f, "invalid --deployment argument: {reason}")
+            }
+            ConfigError::InvalidMutableRange(reason) => {
+                write!(f, "invalid --mutable argument: {reason}")
+            }
+            ConfigError::InvalidTarget(reason) => {
+                write!(f, "invalid --target argument: {reason}")
+            }
+            ConfigError::InvalidUntil => write!(
+                f,
+                "invalid --until argument: expected leading/total zero bytes like `4/8`"
+            ),
+            ConfigError::SaltReverts(preset, reason) => write!(
+                f,
+                "the {preset} factory would revert on the salts being searched for: {reason}"
+            ),
+            ConfigError::OutOfRange { argument, value } => {
+                let valid = match argument {
+                    Argument::TotalZeroes => "0..=20 | 255",
+                    _ => "0..=20",
+                };
+                write!(
+                    f,
+                    "invalid value {value} for {argument} argument. (valid: {valid})"
+                )
+            }
+            ConfigError::Read {
+                argument,
+                path,
+                source,
+            } => write!(
+                f,
+                "could not read {argument} file {}: {source}",
+                path.display()
+            ),
+            ConfigError::InvalidInitCode { argument, reason } => {
+                write!(f, "invalid init code in {argument} argument: {reason}")
+            }
+            ConfigError::UnlinkedLibraries(placeholders) => write!(
+                f,
+                "--artifact bytecode contains unlinked library placeholders ({}); link the \
+                 libraries before searching",
+                placeholders.join(", ")
+            ),
+            ConfigError::InvalidConstructorSig(reason) => {
+                write!(f, "invalid --constructor-sig argument: {reason}")
+            }
+            ConfigError::ConstructorArgCount { expected, actual } => write!(
+                f,
+                "--constructor-sig takes {expected} arguments but {actual} --constructor-args \
+                 were given"
+            ),
+            ConfigError::InvalidConstructorArg { index, reason } => {
+                write!(
+                    f,
+                    "invalid --constructor-args value #{}: {reason}",
+                    index + 1
+                )
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Read { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
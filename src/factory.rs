@@ -0,0 +1,158 @@
+use crate::createx::CREATEX_FACTORY;
+use crate::error::ConfigError;
+use crate::salt::SaltLayout;
+use crate::{abi, keccak256};
+use alloy_primitives::hex;
+use serde::Deserialize;
+use std::fmt;
+
+/// A well-known CREATE2 factory, whose address, salt rules and calldata are
+/// known, so that salts are searched for the way the factory will use them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FactoryPreset {
+    /// Arachnid's deterministic deployment proxy, called with the salt
+    /// followed by the init code, and without any caller binding.
+    Arachnid,
+    /// 0age's `ImmutableCreate2Factory`, whose `safeCreate2` reverts unless
+    /// the salt begins with the caller or with 20 zero bytes.
+    ImmutableCreate2,
+    /// The EIP-2470 singleton factory, whose `deploy` doesn't bind the salt
+    /// to the caller.
+    Eip2470,
+    /// CreateX, whose salt rules are selected with --createx.
+    Createx,
+}
+
+impl FactoryPreset {
+    /// The address that the factory is deployed at on every chain.
+    pub(crate) fn address(self) -> &'static str {
+        match self {
+            FactoryPreset::Arachnid => "0x4e59b44847b379578588920cA78FbF26c0B4956C",
+            FactoryPreset::ImmutableCreate2 => "0x0000000000FFe8B47B3e2130213B802212439497",
+            FactoryPreset::Eip2470 => "0xce0042B868300000d44A59004Da54A005ffdcf9f",
+            FactoryPreset::Createx => CREATEX_FACTORY,
+        }
+    }
+
+    /// The function that the factory deploys with, or `None` for Arachnid's
+    /// proxy, which takes the salt and init code as raw calldata.
+    pub(crate) fn function(self, create3: bool) -> Option<&'static str> {
+        match self {
+            FactoryPreset::Arachnid => None,
+            FactoryPreset::ImmutableCreate2 => Some("safeCreate2(bytes32,bytes)"),
+            FactoryPreset::Eip2470 => Some("deploy(bytes,bytes32)"),
+            FactoryPreset::Createx if create3 => Some("deployCreate3(bytes32,bytes)"),
+            FactoryPreset::Createx => Some("deployCreate2(bytes32,bytes)"),
+        }
+    }
+
+    /// Encode the calldata that deploys the init code with the salt.
+    pub(crate) fn calldata(self, create3: bool, salt: &[u8; 32], init_code: &[u8]) -> Vec<u8> {
+        let Some(function) = self.function(create3) else {
+            return [salt.as_slice(), init_code].concat();
+        };
+
+        let (salt, init_code) = (hex::encode(salt), hex::encode(init_code));
+        let args = match self {
+            FactoryPreset::Eip2470 => [init_code, salt],
+            _ => [salt, init_code],
+        };
+
+        let mut calldata = keccak256(function.as_bytes())[..4].to_vec();
+        calldata.extend(abi::encode_constructor_args(function, &args).unwrap());
+        calldata
+    }
+
+    /// Whether the factory checks that salts beginning with a caller are used
+    /// by that caller, so that they can't be frontrun. Arachnid's proxy and
+    /// the EIP-2470 singleton never check the caller, while CreateX only does
+    /// with permissioned deploy protection (see `CreatexGuard`).
+    pub(crate) fn binds_caller(self) -> bool {
+        match self {
+            FactoryPreset::ImmutableCreate2 | FactoryPreset::Createx => true,
+            FactoryPreset::Arachnid | FactoryPreset::Eip2470 => false,
+        }
+    }
+
+    /// Refuse salt layouts that the factory would revert on, other than those
+    /// of CreateX, whose layouts are built from its guard.
+    pub(crate) fn check_salt_layout(
+        self,
+        salt_layout: &SaltLayout,
+        calling_address: &[u8; 20],
+    ) -> Result<(), ConfigError> {
+        match self {
+            FactoryPreset::ImmutableCreate2 => {
+                let prefix = salt_layout.fixed_prefix();
+                let reverts = prefix.len() < 20
+                    || (prefix[..20] != *calling_address && prefix[..20] != [0; 20]);
+                if reverts {
+                    return Err(ConfigError::SaltReverts(
+                        self,
+                        "salts must begin with the caller or with 20 zero bytes",
+                    ));
+                }
+                Ok(())
+            }
+            FactoryPreset::Arachnid | FactoryPreset::Eip2470 | FactoryPreset::Createx => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for FactoryPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FactoryPreset::Arachnid => "arachnid",
+            FactoryPreset::ImmutableCreate2 => "immutable-create2",
+            FactoryPreset::Eip2470 => "eip2470",
+            FactoryPreset::Createx => "createx",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALLER: [u8; 20] = [0x11; 20];
+
+    fn check(preset: FactoryPreset, layout: &str) -> bool {
+        let salt_layout = SaltLayout::parse(layout, &CALLER).unwrap();
+        preset.check_salt_layout(&salt_layout, &CALLER).is_ok()
+    }
+
+    #[test]
+    fn immutable_create2_requires_caller_or_zero_prefix() {
+        let preset = FactoryPreset::ImmutableCreate2;
+        assert!(check(preset, "caller,random:6,counter:6"));
+        assert!(check(preset, "zero:20,random:6,counter:6"));
+        assert!(!check(preset, "random:26,counter:6"));
+        assert!(!check(preset, "zero:12,random:14,counter:6"));
+        assert!(!check(
+            preset,
+            "fixed:0x2222222222222222222222222222222222222222,random:6,counter:6"
+        ));
+        assert!(preset.binds_caller());
+    }
+
+    #[test]
+    fn other_presets_accept_any_layout() {
+        for preset in [
+            FactoryPreset::Arachnid,
+            FactoryPreset::Eip2470,
+            FactoryPreset::Createx,
+        ] {
+            assert!(check(preset, "caller,random:6,counter:6"));
+            assert!(check(preset, "zero:20,random:6,counter:6"));
+            assert!(check(preset, "random:26,counter:6"));
+        }
+    }
+
+    #[test]
+    fn only_arachnid_and_eip2470_never_bind_the_caller() {
+        assert!(!FactoryPreset::Arachnid.binds_caller());
+        assert!(!FactoryPreset::Eip2470.binds_caller());
+        assert!(FactoryPreset::Createx.binds_caller());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::error::{Argument, ConfigError};
+use crate::parse;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A Foundry or Hardhat build artifact that the init code was read from.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub path: PathBuf,
+    /// The keccak-256 hash of the bytecode in the artifact, without any
+    /// constructor arguments appended.
+    pub bytecode_hash: [u8; 32],
+}
+
+/// Read creation bytecode from an argument such as `--init-code`, which is
+/// either the path of a file or the hex-encoded bytecode itself. A text file
+/// must hold hex-encoded bytecode (as written by `solc --bin`, ignoring
+/// whitespace), while any other file is taken as the raw bytecode.
+pub(crate) fn read(argument: Argument, arg: &str) -> Result<Vec<u8>, ConfigError> {
+    let path = Path::new(arg);
+    if !path.is_file() {
+        return parse::hex(argument, arg);
+    }
+
+    let contents = fs::read(path).map_err(|source| ConfigError::Read {
+        argument,
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    // text must be hex-encoded (possibly wrapped across lines), as hashing the
+    // text itself would silently give the wrong init code hash; only files that
+    // aren't text are taken as the raw bytecode
+    let init_code = match std::str::from_utf8(&contents) {
+        Ok(text) => {
+            let text: String = text.split_whitespace().collect();
+            parse::hex(argument, &text).map_err(|_| ConfigError::InvalidInitCode {
+                argument,
+                reason: "the file is text but not hex-encoded bytecode (strip any headers, \
+                         such as those of `solc --bin` output, from it)",
+            })?
+        }
+        Err(_) => contents,
+    };
+
+    if init_code.is_empty() {
+        return Err(ConfigError::InvalidInitCode {
+            argument,
+            reason: "the file is empty",
+        });
+    }
+
+    Ok(init_code)
+}
+
+/// Read creation bytecode from a Foundry (`out/X.sol/X.json`, where it is found
+/// under `bytecode.object`) or Hardhat (`artifacts/**/X.json`, where it is
+/// found under `bytecode`) build artifact.
+pub(crate) fn read_artifact(path: &Path) -> Result<Vec<u8>, ConfigError> {
+    let invalid = |reason| ConfigError::InvalidInitCode {
+        argument: Argument::Artifact,
+        reason,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        argument: Argument::Artifact,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let Ok(artifact) = serde_json::from_str::<Value>(&contents) else {
+        return Err(invalid("the file is not valid JSON"));
+    };
+
+    let bytecode = match &artifact["bytecode"] {
+        Value::String(bytecode) => bytecode,
+        Value::Object(bytecode) => match bytecode.get("object") {
+            Some(Value::String(bytecode)) => bytecode,
+            _ => return Err(invalid("the artifact has no `bytecode.object` string")),
+        },
+        _ => return Err(invalid("the artifact has no `bytecode` field")),
+    };
+
+    // unlinked libraries are left as 40-character `__$<hash>$__` (or
+    // `__<name>__`) placeholders in place of their addresses
+    let placeholders = unlinked_placeholders(bytecode);
+    if !placeholders.is_empty() {
+        return Err(ConfigError::UnlinkedLibraries(placeholders));
+    }
+
+    let init_code = parse::hex(Argument::Artifact, bytecode)?;
+    if init_code.is_empty() {
+        return Err(invalid(
+            "the bytecode is empty (is the contract abstract or an interface?)",
+        ));
+    }
+
+    Ok(init_code)
+}
+
+/// Find the distinct library placeholders left in unlinked bytecode.
+fn unlinked_placeholders(bytecode: &str) -> Vec<String> {
+    let mut placeholders: Vec<String> = vec![];
+
+    let mut rest = bytecode;
+    while let Some(start) = rest.find("__") {
+        let end = (start + 40).min(rest.len());
+        let placeholder = rest[start..end].to_string();
+        if !placeholders.contains(&placeholder) {
+            placeholders.push(placeholder);
+        }
+        rest = &rest[end..];
+    }
+
+    placeholders
+}
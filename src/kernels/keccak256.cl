@@ -236,8 +236,12 @@ __kernel void hashMessage(
 
   nonce_t nonce;
 
-  // write the control character
-  sponge[0] = 0xffu;
+  // populate the nonce
+  nonce.uint32_t[0] = get_global_id(0);
+  nonce.uint32_t[1] = d_nonce[0];
+
+  // write the control character (0xff, or the configured CREATE2 prefix)
+  sponge[0] = S_0;
 
   sponge[1] = S_1;
   sponge[2] = S_2;
@@ -259,6 +263,9 @@ __kernel void hashMessage(
   sponge[18] = S_18;
   sponge[19] = S_19;
   sponge[20] = S_20;
+
+  // write the salt, whose bytes are fixed, random (from the message) or
+  // taken from the nonce according to the salt layout
   sponge[21] = S_21;
   sponge[22] = S_22;
   sponge[23] = S_23;
@@ -279,25 +286,18 @@ __kernel void hashMessage(
   sponge[38] = S_38;
   sponge[39] = S_39;
   sponge[40] = S_40;
-
-  sponge[41] = d_message[0];
-  sponge[42] = d_message[1];
-  sponge[43] = d_message[2];
-  sponge[44] = d_message[3];
-
-  // populate the nonce
-  nonce.uint32_t[0] = get_global_id(0);
-  nonce.uint32_t[1] = d_nonce[0];
-
-  // populate the body of the message with the nonce
-  sponge[45] = nonce.uint8_t[0];
-  sponge[46] = nonce.uint8_t[1];
-  sponge[47] = nonce.uint8_t[2];
-  sponge[48] = nonce.uint8_t[3];
-  sponge[49] = nonce.uint8_t[4];
-  sponge[50] = nonce.uint8_t[5];
-  sponge[51] = nonce.uint8_t[6];
-  sponge[52] = nonce.uint8_t[7];
+  sponge[41] = S_41;
+  sponge[42] = S_42;
+  sponge[43] = S_43;
+  sponge[44] = S_44;
+  sponge[45] = S_45;
+  sponge[46] = S_46;
+  sponge[47] = S_47;
+  sponge[48] = S_48;
+  sponge[49] = S_49;
+  sponge[50] = S_50;
+  sponge[51] = S_51;
+  sponge[52] = S_52;
 
   sponge[53] = S_53;
   sponge[54] = S_54;
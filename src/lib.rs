@@ -1,24 +1,73 @@
 #![warn(unused_crate_dependencies, unreachable_pub)]
 #![deny(unused_must_use, rust_2018_idioms)]
 
-use alloy_primitives::{hex, Address, FixedBytes};
-use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use alloy_primitives::{hex, Address};
 use console::Term;
 use fs4::FileExt;
 use ocl::{Buffer, Context, Device, MemFlags, Platform, ProQue, Program, Queue};
 use rand::{thread_rng, Rng};
 use rayon::prelude::*;
 use separator::Separatable;
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt::Write as _;
 use std::fs::{File, OpenOptions};
+use std::hint::black_box;
 use std::io::prelude::*;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use terminal_size::{terminal_size, Height};
 use tiny_keccak::{Hasher, Keccak};
 
+mod abi;
+mod account;
+mod children;
+pub mod cli;
+mod clone;
+mod createx;
+mod deployer;
+mod deployment;
+mod derivation;
+mod error;
+mod factory;
+mod init_code;
+mod mutate;
+mod parse;
+mod profile;
+mod results;
 mod reward;
+mod safe;
+mod salt;
+mod scorer;
+mod target;
+mod tron;
+mod zksync;
+pub use account::AccountFactory;
+pub use children::Child;
+pub use clone::{CloneConfig, CloneKind};
+pub use createx::CreatexGuard;
+pub use deployer::{deployer, plan, DeployerConfig, PlanConfig};
+pub use deployment::Deployment;
+pub use derivation::{Derivation, CREATE3_PRESETS};
+pub use error::{Argument, ConfigError};
+pub use factory::FactoryPreset;
+pub use init_code::Artifact;
+pub use mutate::{mutate, MutateConfig};
+pub use profile::Profile;
 pub use reward::Reward;
+pub use safe::SafeVersion;
+pub use salt::{
+    SaltHash, SaltLayout, SaltRegion, UnboundSalt, DEFAULT_GPU_SALT_LAYOUT, DEFAULT_SALT_LAYOUT,
+};
+pub use scorer::{Score, Scorer};
+pub use target::{Quality, Target};
+
+use account::AccountPreset;
+use cli::SearchArgs;
+use results::{ResultLine, RunHeader, RunSettings};
+use safe::SafePreset;
 
 // workset size (tweak this!)
 const WORK_SIZE: u32 = 0x4000000; // max. 0x15400000 to abs. max 0xffffffff
@@ -26,6 +75,7 @@ const WORK_SIZE: u32 = 0x4000000; // max. 0x15400000 to abs. max 0xffffffff
 const WORK_FACTOR: u128 = (WORK_SIZE as u128) / 1_000_000;
 const CONTROL_CHARACTER: u8 = 0xff;
 const MAX_INCREMENTER: u64 = 0xffffffffffff;
+const DEFAULT_OUTPUT: &str = "efficient_addresses.txt";
 
 static KERNEL_SRC: &str = include_str!("./kernels/keccak256.cl");
 
@@ -34,204 +84,911 @@ static KERNEL_SRC: &str = include_str!("./kernels/keccak256.cl");
 /// the contract calling CREATE2 has frontrunning protection in place - if not
 /// applicable to your use-case you can set it to the null address)*, and the
 /// keccak-256 hash of the bytecode that is provided by the contract calling
-/// CREATE2 that will be used to initialize the new contract. An additional set
-/// of three optional values may be provided: a device to target for OpenCL GPU
-/// search, a threshold for leading zeroes to search for, and a threshold for
-/// total zeroes to search for.
+/// CREATE2 that will be used to initialize the new contract (or that bytecode
+/// itself, either hex-encoded, as a file or as a Foundry or Hardhat build
+/// artifact, along with an optional constructor
+/// signature and arguments to ABI-encode and append to it, in which case it is
+/// hashed here and the resultant hash is used throughout). An additional set
+/// of four optional values may be provided: a device to target for OpenCL GPU
+/// search, a threshold for leading zeroes to search for, a threshold for total
+/// zeroes to search for, and the file that found salts are appended to. The
+/// layout of each salt may also be customized (see `SaltLayout`), and CREATE3
+/// addresses may be searched for in place of CREATE2 addresses by giving the
+/// init code hash of the CREATE3 proxy (see `Derivation`). All of these may
+/// also be read from a profile in a TOML config file. Addresses are scored by
+/// `Reward` unless another `Scorer` is set.
 pub struct Config {
     pub factory_address: [u8; 20],
     pub calling_address: [u8; 20],
     pub init_code_hash: [u8; 32],
-    pub gpu_device: u8,
+    pub init_code: Option<Vec<u8>>,
+    pub artifact: Option<Artifact>,
+    pub salt_layout: SaltLayout,
+    pub hashed_salt: Option<SaltHash>,
+    pub derivation: Derivation,
+    pub createx: Option<CreatexGuard>,
+    pub factory_preset: Option<FactoryPreset>,
+    pub create2_prefix: u8,
+    pub tron: bool,
+    pub children: Vec<Child>,
+    pub deployments: Vec<Deployment>,
+    pub targets: Vec<Target>,
+    pub until: Option<Quality>,
+    pub gpu_device: Option<u8>,
     pub leading_zeroes_threshold: u8,
     pub total_zeroes_threshold: u8,
+    pub scorer: Arc<dyn Scorer>,
+    pub output: PathBuf,
 }
 
-/// Validate the provided arguments and construct the Config struct.
+/// Validate the provided arguments and construct the Config struct. Addresses
+/// and hashes are hex-encoded with an optional `0x` prefix, and mixed-case
+/// addresses must match their EIP-55 checksum.
 impl Config {
-    pub fn new(mut args: std::env::Args) -> Result<Self, &'static str> {
-        // get args, skipping first arg (program name)
-        args.next();
-
-        let Some(factory_address_string) = args.next() else {
-            return Err("didn't get a factory_address argument");
+    pub fn new(args: &SearchArgs) -> Result<Self, ConfigError> {
+        // the Safe preset supplies the factory, init code and hashed salt
+        let safe = match args.safe.version {
+            Some(version) => Some(SafePreset::new(
+                &args.safe,
+                version,
+                args.factory.as_deref(),
+            )?),
+            None => None,
         };
-        let Some(calling_address_string) = args.next() else {
-            return Err("didn't get a calling_address argument");
+
+        // the smart account presets supply the init code and any hashed salt
+        let account = match args.account.preset {
+            Some(factory) => Some(AccountPreset::new(&args.account, factory)?),
+            None => None,
         };
-        let Some(init_code_hash_string) = args.next() else {
-            return Err("didn't get an init_code_hash argument");
+
+        // minimal proxy clones are built from the implementation instead
+        let clone = match args.clone.kind {
+            Some(_) => Some(CloneConfig::new(&args.clone)?),
+            None => None,
         };
 
-        let gpu_device_string = match args.next() {
-            Some(arg) => arg,
-            None => String::from("255"), // indicates that CPU will be used.
+        // Tron deploys with its own CREATE2 prefix, and its addresses may be
+        // given in base58
+        let create2_prefix = match (&args.create2_prefix, args.tron) {
+            (Some(_), true) => {
+                return Err(ConfigError::Conflicts(
+                    Argument::Tron,
+                    Argument::Create2Prefix,
+                ))
+            }
+            (Some(prefix), false) => parse::fixed::<1>(Argument::Create2Prefix, prefix)?[0],
+            (None, true) => tron::CREATE2_PREFIX,
+            (None, false) => CONTROL_CHARACTER,
         };
-        let leading_zeroes_threshold_string = match args.next() {
-            Some(arg) => arg,
-            None => String::from("3"),
+        let address = |argument, value: &str| match args.tron && value.trim().starts_with('T') {
+            true => tron::parse_base58_address(argument, value),
+            false => parse::address(argument, value),
         };
-        let total_zeroes_threshold_string = match args.next() {
-            Some(arg) => arg,
-            None => String::from("5"),
+
+        // the presets deploy to the EVM, with addresses derived the usual way
+        if args.tron || args.create2_prefix.is_some() {
+            let argument = [
+                (safe.is_some(), Argument::Safe),
+                (account.is_some(), Argument::Account),
+                (args.create3.is_some(), Argument::Create3),
+                (args.createx.is_some(), Argument::Createx),
+                (args.zksync, Argument::ZkSync),
+            ]
+            .into_iter()
+            .find_map(|(given, argument)| given.then_some(argument));
+
+            if let Some(argument) = argument {
+                let prefix = match args.tron {
+                    true => Argument::Tron,
+                    false => Argument::Create2Prefix,
+                };
+                return Err(ConfigError::Conflicts(prefix, argument));
+            }
+        }
+
+        // the factory presets deploy to the EVM with salts that they use as
+        // given, other than CreateX, which guards them as --createx selects
+        if let Some(preset) = args.factory_preset {
+            let argument = [
+                (args.factory.is_some(), Argument::Factory),
+                (safe.is_some(), Argument::Safe),
+                (account.is_some(), Argument::Account),
+                (args.zksync, Argument::ZkSync),
+                (args.tron, Argument::Tron),
+                (args.create2_prefix.is_some(), Argument::Create2Prefix),
+                (args.hashed_salt.is_some(), Argument::HashedSalt),
+                (
+                    args.create3.is_some() && preset != FactoryPreset::Createx,
+                    Argument::Create3,
+                ),
+                (
+                    args.createx.is_some() && preset != FactoryPreset::Createx,
+                    Argument::Createx,
+                ),
+            ]
+            .into_iter()
+            .find_map(|(given, argument)| given.then_some(argument));
+
+            if let Some(argument) = argument {
+                return Err(ConfigError::Conflicts(Argument::FactoryPreset, argument));
+            }
+            if preset == FactoryPreset::Createx && args.createx.is_none() {
+                return Err(ConfigError::Requires(
+                    Argument::FactoryPreset,
+                    Argument::Createx,
+                ));
+            }
+        }
+
+        // convert main arguments from hex strings to fixed arrays
+        let factory_address = match (&safe, &args.factory, args.factory_preset, args.createx) {
+            (Some(safe), _, _, _) => safe.factory_address,
+            (None, Some(factory_address_string), _, _) => {
+                address(Argument::Factory, factory_address_string)?
+            }
+            (None, None, Some(preset), _) => {
+                parse::address(Argument::FactoryPreset, preset.address())?
+            }
+            (None, None, None, Some(_)) => {
+                parse::address(Argument::Factory, createx::CREATEX_FACTORY)?
+            }
+            (None, None, None, None) => return Err(ConfigError::Missing(Argument::Factory)),
         };
 
-        // convert main arguments from hex string to vector of bytes
-        let Ok(factory_address_vec) = hex::decode(factory_address_string) else {
-            return Err("could not decode factory address argument");
+        // unbound salts don't begin with the caller, which is left as the null address
+        let calling_address = match (&args.caller, args.unbound_salt) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::Conflicts(
+                    Argument::UnboundSalt,
+                    Argument::Caller,
+                ))
+            }
+            // CreateX only binds salts to the caller with permissioned deploy
+            // protection
+            (Some(_), None) if args.createx.is_some_and(|guard| !guard.is_permissioned()) => {
+                return Err(ConfigError::Conflicts(Argument::Createx, Argument::Caller))
+            }
+            (Some(calling_address_string), None) => {
+                address(Argument::Caller, calling_address_string)?
+            }
+            (None, Some(_)) => [0; 20],
+            (None, None) if args.createx.is_some_and(|guard| !guard.is_permissioned()) => [0; 20],
+            // the Safe proxy and smart account factories don't check the caller
+            (None, None) if safe.is_some() || account.is_some() => [0; 20],
+            // factories that don't check the caller leave every salt unbound,
+            // while those that do only take unbound salts (e.g. zero in place of
+            // the caller for ImmutableCreate2Factory) with --unbound-salt
+            (None, None)
+                if args
+                    .factory_preset
+                    .is_some_and(|preset| !preset.binds_caller()) =>
+            {
+                [0; 20]
+            }
+            (None, None) if args.factory_preset.is_some() => {
+                return Err(ConfigError::Requires(
+                    Argument::FactoryPreset,
+                    Argument::Caller,
+                ))
+            }
+            (None, None) => return Err(ConfigError::Missing(Argument::Caller)),
         };
-        let Ok(calling_address_vec) = hex::decode(calling_address_string) else {
-            return Err("could not decode calling address argument");
+
+        // CREATE3 addresses don't depend on the init code of the contract, and
+        // the Safe, smart account and clone presets supply the init code of the
+        // proxy
+        let preset = [
+            (safe.is_some(), Argument::Safe),
+            (account.is_some(), Argument::Account),
+            (clone.is_some(), Argument::Clone),
+            (args.create3.is_some(), Argument::Create3),
+        ]
+        .into_iter()
+        .find_map(|(given, argument)| given.then_some(argument));
+
+        if let Some(preset) = preset {
+            let init_code_argument = [
+                (args.init_code_hash.is_some(), Argument::InitCodeHash),
+                (args.init_code.is_some(), Argument::InitCode),
+                (args.artifact.is_some(), Argument::Artifact),
+                (args.constructor_sig.is_some(), Argument::ConstructorSig),
+                (!args.constructor_args.is_empty(), Argument::ConstructorArgs),
+                (args.create3.is_some(), Argument::Create3),
+                (account.is_some(), Argument::Account),
+                (clone.is_some(), Argument::Clone),
+            ]
+            .into_iter()
+            .find_map(|(given, argument)| (given && argument != preset).then_some(argument));
+
+            if let Some(argument) = init_code_argument {
+                return Err(ConfigError::Conflicts(preset, argument));
+            }
+        }
+
+        // the presets and CreateX build EVM init code and salts, which zkSync
+        // Era derives addresses from differently
+        if args.zksync {
+            let evm_argument = [preset, args.createx.is_some().then_some(Argument::Createx)]
+                .into_iter()
+                .flatten()
+                .next();
+
+            if let Some(argument) = evm_argument {
+                return Err(ConfigError::Conflicts(Argument::ZkSync, argument));
+            }
+        }
+
+        // zkSync Era hashes the constructor input separately from the bytecode,
+        // rather than with the constructor arguments appended to it
+        let constructor_args = match &args.constructor_sig {
+            Some(signature) => Some(abi::encode_constructor_args(
+                signature,
+                &args.constructor_args,
+            )?),
+            None if !args.constructor_args.is_empty() => {
+                return Err(ConfigError::Requires(
+                    Argument::ConstructorArgs,
+                    Argument::ConstructorSig,
+                ));
+            }
+            None => None,
         };
-        let Ok(init_code_hash_vec) = hex::decode(init_code_hash_string) else {
-            return Err("could not decode initialization code hash argument");
+
+        // read the init code if it was given, instead of its hash
+        let mut artifact = None;
+        let init_code = match (&args.init_code, &args.artifact) {
+            (Some(init_code_string), _) => Some((
+                Argument::InitCode,
+                init_code::read(Argument::InitCode, init_code_string)?,
+            )),
+            (None, Some(artifact_path)) => {
+                let bytecode = init_code::read_artifact(artifact_path)?;
+                artifact = Some(Artifact {
+                    path: artifact_path.clone(),
+                    bytecode_hash: keccak256(&bytecode),
+                });
+                Some((Argument::Artifact, bytecode))
+            }
+            (None, None) => None,
         };
 
-        // convert from vector to fixed array
-        let Ok(factory_address) = factory_address_vec.try_into() else {
-            return Err("invalid length for factory address argument");
+        // keep the init code (with any constructor arguments) that the factory
+        // is called with, when it is known
+        let mut deployed_init_code = None;
+        let init_code_hash = match (init_code, &args.init_code_hash) {
+            (Some((argument, bytecode)), _) if args.zksync => {
+                zksync::bytecode_hash(argument, &bytecode)?
+            }
+            (Some((_, mut init_code)), _) => {
+                // append the ABI-encoded constructor arguments, if any
+                if let Some(constructor_args) = &constructor_args {
+                    init_code.extend_from_slice(constructor_args);
+                }
+
+                let init_code_hash = keccak256(&init_code);
+                deployed_init_code = Some(init_code);
+                init_code_hash
+            }
+            (None, Some(_)) if args.constructor_sig.is_some() && !args.zksync => {
+                return Err(ConfigError::Requires(
+                    Argument::ConstructorSig,
+                    Argument::InitCode,
+                ));
+            }
+            // with zkSync, the versioned bytecode hash
+            (None, Some(init_code_hash_string)) => {
+                parse::fixed(Argument::InitCodeHash, init_code_hash_string)?
+            }
+            // CREATE3 addresses depend on the init code of the proxy instead,
+            // and the presets build the init code themselves
+            (None, None) => {
+                let preset_init_code = safe
+                    .as_ref()
+                    .map(|safe| safe.init_code.clone())
+                    .or_else(|| account.as_ref().map(|account| account.init_code.clone()))
+                    .or_else(|| clone.as_ref().map(CloneConfig::init_code));
+
+                match (&args.create3, preset_init_code) {
+                    (Some(proxy), _) => derivation::create3_proxy_init_code_hash(proxy)?,
+                    (None, Some(init_code)) => {
+                        let init_code_hash = keccak256(&init_code);
+                        deployed_init_code = Some(init_code);
+                        init_code_hash
+                    }
+                    (None, None) => return Err(ConfigError::Missing(Argument::InitCodeHash)),
+                }
+            }
         };
-        let Ok(calling_address) = calling_address_vec.try_into() else {
-            return Err("invalid length for calling address argument");
+
+        // zkSync Era hashes the constructor input rather than appending it
+        let zksync_derivation = Derivation::ZkSync {
+            constructor_input_hash: keccak256(&constructor_args.unwrap_or_default()),
         };
-        let Ok(init_code_hash) = init_code_hash_vec.try_into() else {
-            return Err("invalid length for initialization code hash argument");
+        let derivation = match (&args.create3, args.zksync) {
+            (Some(_), true) => {
+                return Err(ConfigError::Conflicts(Argument::ZkSync, Argument::Create3))
+            }
+            (Some(_), false) => Derivation::Create3,
+            (None, true) => zksync_derivation,
+            (None, false) => Derivation::Create2,
         };
 
-        // convert gpu arguments to u8 values
-        let Ok(gpu_device) = gpu_device_string.parse::<u8>() else {
-            return Err("invalid gpu device value");
+        // CreateX selects its guard by the first 21 bytes of the salt, and the
+        // built-in layouts have an 8-byte counter on the GPU and 6 on the CPU
+        let gpu = args.gpu_device.is_some();
+        let salt_layout = match (&args.salt_layout, args.unbound_salt, args.createx) {
+            (Some(_), _, Some(_)) => {
+                return Err(ConfigError::Conflicts(
+                    Argument::Createx,
+                    Argument::SaltLayout,
+                ))
+            }
+            (_, Some(_), Some(_)) => {
+                return Err(ConfigError::Conflicts(
+                    Argument::Createx,
+                    Argument::UnboundSalt,
+                ))
+            }
+            (Some(_), Some(_), None) => {
+                return Err(ConfigError::Conflicts(
+                    Argument::UnboundSalt,
+                    Argument::SaltLayout,
+                ))
+            }
+            (Some(template), None, None) => SaltLayout::parse(template, &calling_address)?,
+            (None, Some(unbound_salt), None) => {
+                SaltLayout::parse(unbound_salt.salt_layout(gpu), &calling_address)?
+            }
+            (None, None, Some(guard)) => guard.salt_layout(&calling_address, gpu)?,
+            (None, None, None) if gpu => {
+                SaltLayout::parse(DEFAULT_GPU_SALT_LAYOUT, &calling_address)?
+            }
+            (None, None, None) => SaltLayout::parse(DEFAULT_SALT_LAYOUT, &calling_address)?,
         };
-        let Ok(leading_zeroes_threshold) = leading_zeroes_threshold_string.parse::<u8>() else {
-            return Err("invalid leading zeroes threshold value supplied");
+
+        // refuse salts that the factory preset would revert on
+        if let Some(preset) = args.factory_preset {
+            preset.check_salt_layout(&salt_layout, &calling_address)?;
+        }
+
+        // the Safe, smart account and CreateX presets hash the salt themselves
+        let preset_hashed_salt = match (&safe, &account, args.createx) {
+            (Some(_), _, Some(_)) => {
+                return Err(ConfigError::Conflicts(Argument::Safe, Argument::Createx))
+            }
+            (_, Some(_), Some(_)) => {
+                return Err(ConfigError::Conflicts(Argument::Account, Argument::Createx))
+            }
+            (Some(safe), _, None) => Some((
+                Argument::Safe,
+                Some(SaltHash::new(safe.initializer_hash.to_vec(), vec![])),
+            )),
+            (None, Some(account), None) => Some((Argument::Account, account.hashed_salt.clone())),
+            (None, None, Some(guard)) => {
+                let chain_id = match (args.chain_id, guard.is_cross_chain_protected()) {
+                    (Some(chain_id), _) => chain_id,
+                    (None, false) => 0,
+                    (None, true) => {
+                        return Err(ConfigError::Requires(Argument::Createx, Argument::ChainId))
+                    }
+                };
+                Some((
+                    Argument::Createx,
+                    Some(guard.hashed_salt(&calling_address, chain_id)),
+                ))
+            }
+            (None, None, None) => None,
         };
-        let Ok(total_zeroes_threshold) = total_zeroes_threshold_string.parse::<u8>() else {
-            return Err("invalid total zeroes threshold value supplied");
+        if args.chain_id.is_some() && args.createx.is_none() {
+            return Err(ConfigError::Requires(Argument::ChainId, Argument::Createx));
+        }
+
+        let hashed_salt = match (&args.hashed_salt, &preset_hashed_salt) {
+            (Some(_), Some((preset, _))) => {
+                return Err(ConfigError::Conflicts(*preset, Argument::HashedSalt))
+            }
+            (Some(template), None) => Some(SaltHash::parse(template)?),
+            (None, Some((_, hashed_salt))) => hashed_salt.clone(),
+            (None, None) => None,
         };
+        if args.gpu_device.is_some() && hashed_salt.is_some() {
+            let argument = preset_hashed_salt.map_or(Argument::HashedSalt, |(preset, _)| preset);
+            return Err(ConfigError::GpuUnsupported(argument));
+        }
+
+        match derivation {
+            Derivation::Create2 => {}
+            _ if args.gpu_device.is_none() => {}
+            Derivation::Create3 => return Err(ConfigError::GpuUnsupported(Argument::Create3)),
+            Derivation::ZkSync { .. } => return Err(ConfigError::GpuUnsupported(Argument::ZkSync)),
+        }
+
+        // the kernel fills the counter with the work item id and a 4-byte nonce
+        if args.gpu_device.is_some() && salt_layout.counter_range().len() != 8 {
+            return Err(ConfigError::InvalidSaltLayout(
+                "the GPU requires a counter region of 8 bytes",
+            ));
+        }
+
+        let leading_zeroes_threshold = args.leading_zeroes.unwrap_or(3);
+        let total_zeroes_threshold = args.total_zeroes.unwrap_or(5);
 
         if leading_zeroes_threshold > 20 {
-            return Err("invalid value for leading zeroes threshold argument. (valid: 0..=20)");
+            return Err(ConfigError::OutOfRange {
+                argument: Argument::LeadingZeroes,
+                value: leading_zeroes_threshold,
+            });
         }
         if total_zeroes_threshold > 20 && total_zeroes_threshold != 255 {
-            return Err("invalid value for total zeroes threshold argument. (valid: 0..=20 | 255)");
+            return Err(ConfigError::OutOfRange {
+                argument: Argument::TotalZeroes,
+                value: total_zeroes_threshold,
+            });
+        }
+
+        // the children of the deployed contract must meet their own thresholds
+        // (by default those of the parent), which only the CPU checks
+        let children = match &args.children {
+            Some(children) => {
+                Child::parse_list(children, leading_zeroes_threshold, total_zeroes_threshold)?
+            }
+            None => vec![],
+        };
+        if !children.is_empty() {
+            if args.gpu_device.is_some() {
+                return Err(ConfigError::GpuUnsupported(Argument::Children));
+            }
+            if args.zksync {
+                return Err(ConfigError::Conflicts(Argument::Children, Argument::ZkSync));
+            }
+            if args.tron {
+                return Err(ConfigError::Conflicts(Argument::Children, Argument::Tron));
+            }
+        }
+
+        // every other deployment of the contract must meet the thresholds as
+        // well, which only the CPU checks
+        let deployments = args
+            .deployments
+            .iter()
+            .map(|deployment| Deployment::parse(deployment, derivation, zksync_derivation))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !deployments.is_empty() {
+            if args.gpu_device.is_some() {
+                return Err(ConfigError::GpuUnsupported(Argument::Deployment));
+            }
+            // CreateX binds salts to the chain and Tron to its own prefix, and
+            // children are only derived from the address of the search
+            let argument = [
+                (args.createx.is_some(), Argument::Createx),
+                (args.tron, Argument::Tron),
+                (args.create2_prefix.is_some(), Argument::Create2Prefix),
+                (!children.is_empty(), Argument::Children),
+            ]
+            .into_iter()
+            .find_map(|(given, argument)| given.then_some(argument));
+
+            if let Some(argument) = argument {
+                return Err(ConfigError::Conflicts(Argument::Deployment, argument));
+            }
+        }
+
+        // other targets are searched in the same pass, as long as they (and the
+        // search itself, with --until) haven't reached their quality, which
+        // only the CPU checks
+        let until = match &args.until {
+            Some(until) => Some(Quality::parse(until).ok_or(ConfigError::InvalidUntil)?),
+            None => None,
+        };
+        let targets = args
+            .targets
+            .iter()
+            .map(|target| Target::parse(target, &calling_address))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = args
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT));
+        if args.gpu_device.is_some() {
+            if !targets.is_empty() {
+                return Err(ConfigError::GpuUnsupported(Argument::Target));
+            }
+            if until.is_some() {
+                return Err(ConfigError::GpuUnsupported(Argument::Until));
+            }
+        }
+        if !targets.is_empty() {
+            // CREATE3 addresses don't depend on the init code of the contract,
+            // zkSync addresses depend on the constructor input of the search,
+            // and the children and other deployments are those of the search
+            let argument = [
+                (args.create3.is_some(), Argument::Create3),
+                (args.zksync, Argument::ZkSync),
+                (!children.is_empty(), Argument::Children),
+                (!deployments.is_empty(), Argument::Deployment),
+            ]
+            .into_iter()
+            .find_map(|(given, argument)| given.then_some(argument));
+
+            if let Some(argument) = argument {
+                return Err(ConfigError::Conflicts(Argument::Target, argument));
+            }
+
+            let mut outputs: Vec<_> = targets.iter().map(|target| &target.output).collect();
+            outputs.push(&output);
+            outputs.sort();
+            outputs.dedup();
+            if outputs.len() != targets.len() + 1 {
+                return Err(ConfigError::InvalidTarget(
+                    "each target needs an output file of its own",
+                ));
+            }
+
+            // a target's caller only takes the place of a caller given at the
+            // start of salts bound to it (not zero in place of one), and CreateX
+            // hashes the caller of the search into each salt as well
+            let bound = args.caller.is_some()
+                && salt_layout.is_bound_to(&calling_address)
+                && args.createx.is_none();
+            for target in &targets {
+                if target.calling_address != calling_address && !bound {
+                    return Err(ConfigError::InvalidTarget(
+                        "a caller of its own needs salts bound to the caller (not unbound or \
+                         guarded by CreateX)",
+                    ));
+                }
+                if let Some(preset) = args.factory_preset {
+                    let salt_layout =
+                        salt_layout.rebound(&calling_address, &target.calling_address);
+                    preset.check_salt_layout(&salt_layout, &target.calling_address)?;
+                }
+            }
         }
 
         Ok(Self {
             factory_address,
             calling_address,
             init_code_hash,
-            gpu_device,
+            init_code: deployed_init_code,
+            artifact,
+            salt_layout,
+            hashed_salt,
+            derivation,
+            createx: args.createx,
+            factory_preset: args.factory_preset,
+            create2_prefix,
+            tron: args.tron,
+            children,
+            deployments,
+            gpu_device: args.gpu_device,
             leading_zeroes_threshold,
             total_zeroes_threshold,
+            targets,
+            until,
+            scorer: Arc::new(Reward::new()),
+            output,
         })
     }
+
+    /// The leading and total zero bytes that every address must have when
+    /// children or other deployments are searched (either one suffices).
+    pub(crate) fn threshold(&self) -> Quality {
+        Quality {
+            leading_zeroes: self.leading_zeroes_threshold,
+            total_zeroes: self.total_zeroes_threshold,
+        }
+    }
 }
 
 /// Given a Config object with a factory address, a caller address, and a
 /// keccak-256 hash of the contract initialization code, search for salts that
 /// will enable the factory contract to deploy a contract to a gas-efficient
-/// address via CREATE2.
+/// address via CREATE2 (or via CREATE3, through a proxy deployed via CREATE2).
 ///
-/// The 32-byte salt is constructed as follows:
+/// The 32-byte salt is constructed according to the salt layout, by default:
 ///   - the 20-byte calling address (to prevent frontrunning)
 ///   - a random 6-byte segment (to prevent collisions with other runs)
 ///   - a 6-byte nonce segment (incrementally stepped through during the run)
 ///
+/// A layout without random bytes has a single batch of salts, so the search
+/// stops once the counter has been stepped through.
+///
 /// When a salt that will result in the creation of a gas-efficient contract
-/// address is found, it will be appended to `efficient_addresses.txt` along
-/// with the resultant address and the "value" (i.e. approximate rarity) of the
-/// resultant address.
+/// address is found, it will be appended to the output file (by default
+/// `efficient_addresses.txt`) along with the resultant address and the "value"
+/// (i.e. approximate rarity) of the resultant address. When children deployed
+/// by the contract via CREATE (or other deployments of the contract) are
+/// searched along with it, the contract and each child (or deployment) must
+/// instead meet their thresholds, and the address and value of each follow.
+///
+/// Other targets deployed by the same factory are evaluated against each
+/// candidate salt in the same pass, with salts found for them appended to
+/// their own output files. Each drops out of the search once it has reached
+/// its requested quality, and the search stops once every target has.
 pub fn cpu(config: Config) -> Result<(), Box<dyn Error>> {
-    // (create if necessary) and open a file where found salts will be written
-    let file = output_file();
+    println!(
+        "Searching on the CPU using init code hash 0x{}...",
+        hex::encode(config.init_code_hash)
+    );
+    match config.derivation {
+        Derivation::Create2 => {}
+        Derivation::Create3 => println!(
+            "Deriving the addresses deployed by the CREATE3 proxy with that init code hash"
+        ),
+        Derivation::ZkSync { .. } => {
+            println!("Deriving zkSync Era addresses with that as the versioned bytecode hash")
+        }
+    }
+    if !config.children.is_empty() {
+        let nonces: Vec<_> = config
+            .children
+            .iter()
+            .map(|child| child.nonce.to_string())
+            .collect();
+        println!(
+            "Requiring the children deployed via CREATE at nonces {} to meet their thresholds too",
+            nonces.join(", ")
+        );
+    }
+    if !config.deployments.is_empty() {
+        println!(
+            "Requiring the {} other deployments to meet the thresholds too",
+            config.deployments.len()
+        );
+    }
+    if !config.targets.is_empty() {
+        println!(
+            "Searching for {} other targets in the same pass",
+            config.targets.len()
+        );
+    }
+    if let Some(until) = config.until {
+        println!(
+            "Stopping once an address with {} leading or {} total zero bytes is found",
+            until.leading_zeroes, until.total_zeroes
+        );
+    }
+    if let Some(preset) = config.factory_preset {
+        let create3 = config.derivation == Derivation::Create3;
+        match preset.function(create3) {
+            Some(function) => println!(
+                "Searching salts for the {preset} factory; pass each salt found to {function}"
+            ),
+            None => println!(
+                "Searching salts for the {preset} factory; call it with each salt found followed by \
+                 the init code"
+            ),
+        }
+    }
+    if let Some(guard) = config.createx {
+        println!(
+            "Guarding salts like CreateX with {guard}; pass each salt found to {}",
+            createx_function(&config)
+        );
+    }
+
+    // warn if the salts found can be frontrun
+    warn_on_unbound_salt(&config);
+
+    // warn if the artifact has changed since it was last searched with
+    results::warn_on_artifact_change(&config);
+
+    // the search itself and any other targets searched in the same pass, each
+    // with a file where found salts will be written, (created if necessary and)
+    // opened with the parameters of this run recorded in it
+    let mut targets = vec![Target {
+        calling_address: config.calling_address,
+        init_code_hash: config.init_code_hash,
+        output: config.output.clone(),
+        until: config.until,
+    }];
+    targets.extend(config.targets.iter().cloned());
+    let files: Vec<File> = targets
+        .iter()
+        .enumerate()
+        .map(|(i, target)| {
+            let file = output_file(&target.output);
+            let header = match i {
+                0 => RunHeader::new(&config),
+                _ => RunHeader::for_target(&config, target),
+            };
+            write_run_header(&file, &target.output, &header);
+            file
+        })
+        .collect();
+
+    // with other targets, each find is labelled with the file it is written to
+    let labels: Vec<String> = targets
+        .iter()
+        .map(|target| match config.targets.is_empty() {
+            true => String::new(),
+            false => format!("[{}] ", target.output.display()),
+        })
+        .collect();
+
+    // targets drop out of the search once they have reached their quality
+    let done: Vec<AtomicBool> = targets.iter().map(|_| AtomicBool::new(false)).collect();
+
+    // with children or other deployments, addresses are held to the thresholds
+    let nested = !config.children.is_empty() || !config.deployments.is_empty();
+    let threshold = config.threshold();
 
-    // create object for computing rewards (relative rarity) for a given address
-    let rewards = Reward::new();
+    // the counter region is stepped through, so everything before it is hashed once
+    let counter = config.salt_layout.counter_range();
+    let max_counter = config.salt_layout.max_counter().min(MAX_INCREMENTER);
 
     // begin searching for addresses
     loop {
-        // header: 0xff ++ factory ++ caller ++ salt_random_segment (47 bytes)
-        let mut header = [0; 47];
-        header[0] = CONTROL_CHARACTER;
-        header[1..21].copy_from_slice(&config.factory_address);
-        header[21..41].copy_from_slice(&config.calling_address);
-        header[41..].copy_from_slice(&FixedBytes::<6>::random()[..]);
+        // salt with fixed and random regions filled, and the counter set to zero
+        let mut random = vec![0; config.salt_layout.random_len()];
+        thread_rng().fill(&mut random[..]);
+        let salt_template = config.salt_layout.salt(&random, 0);
+
+        let headers: Vec<_> = targets
+            .iter()
+            .map(|target| {
+                // each target's salt begins with its own caller
+                let salt_template = config.salt_layout.rebind(
+                    &salt_template,
+                    &config.calling_address,
+                    &target.calling_address,
+                );
 
-        // create new hash object
-        let mut hash_header = Keccak::v256();
+                // header: 0xff ++ factory ++ salt up to the counter, unless the
+                // salt is hashed first, in which case its header is prefix ++
+                // salt up to the counter instead
+                let mut header = config
+                    .derivation
+                    .preimage_header(config.create2_prefix, &config.factory_address);
+                let salt_header = config.hashed_salt.as_ref().map(|hashed_salt| {
+                    let mut salt_header = Keccak::v256();
+                    salt_header.update(hashed_salt.prefix());
+                    salt_header.update(&salt_template[..counter.start]);
+                    salt_header
+                });
+                if salt_header.is_none() {
+                    header.extend_from_slice(&salt_template[..counter.start]);
+                }
 
-        // update hash with header
-        hash_header.update(&header);
+                // create new hash object
+                let mut hash_header = Keccak::v256();
+
+                // update hash with header
+                hash_header.update(&header);
+
+                (salt_template, hash_header, salt_header)
+            })
+            .collect();
 
-        // iterate over a 6-byte nonce and compute each address
-        (0..MAX_INCREMENTER)
+        // iterate over the counter and compute each address
+        let finished = (0..=max_counter)
             .into_par_iter() // parallelization
-            .for_each(|salt| {
+            .try_for_each(|salt| {
                 let salt = salt.to_le_bytes();
-                let salt_incremented_segment = &salt[..6];
-
-                // clone the partially-hashed object
-                let mut hash = hash_header.clone();
+                let salt_incremented_segment = &salt[..counter.len()];
+
+                let search = |i: usize| {
+                    let target = &targets[i];
+                    let (salt_template, hash_header, salt_header) = &headers[i];
+
+                    // clone the partially-hashed object
+                    let mut hash = hash_header.clone();
+
+                    // update with body and footer
+                    if let Some(salt_header) = salt_header {
+                        let mut salt_hash = salt_header.clone();
+                        salt_hash.update(salt_incremented_segment);
+                        salt_hash.update(&salt_template[counter.end..]);
+                        salt_hash.update(config.hashed_salt.as_ref().unwrap().suffix());
+
+                        let mut create2_salt = [0; 32];
+                        salt_hash.finalize(&mut create2_salt);
+                        hash.update(&create2_salt);
+                    } else {
+                        hash.update(salt_incremented_segment);
+                        hash.update(&salt_template[counter.end..]);
+                    }
+                    hash.update(&target.init_code_hash);
+                    hash.update(config.derivation.preimage_footer());
 
-                // update with body and footer (total: 38 bytes)
-                hash.update(salt_incremented_segment);
-                hash.update(&config.init_code_hash);
+                    // hash the payload and get the result
+                    let mut res: [u8; 32] = [0; 32];
+                    hash.finalize(&mut res);
 
-                // hash the payload and get the result
-                let mut res: [u8; 32] = [0; 32];
-                hash.finalize(&mut res);
+                    // get the address that results from the hash
+                    let address = config.derivation.address(&res[12..]);
 
-                // get the address that results from the hash
-                let address = <&Address>::try_from(&res[12..]).unwrap();
-
-                // count total and leading zero bytes
-                let mut total = 0;
-                let mut leading = 21;
-                for (i, &b) in address.iter().enumerate() {
-                    if b == 0 {
-                        total += 1;
-                    } else if leading == 21 {
-                        // set leading on finding non-zero byte
-                        leading = i;
+                    // with children or other deployments, the address and every
+                    // other address must reach the thresholds instead of the
+                    // address being scored
+                    if nested && !config.scorer.reaches(&address, threshold) {
+                        return;
                     }
-                }
 
-                // only proceed if there are at least three zero bytes
-                if total < 3 {
-                    return;
-                }
+                    // score the address
+                    let score = config.scorer.score(&address);
 
-                // look up the reward amount
-                let key = leading * 20 + total;
-                let reward_amount = rewards.get(&key);
+                    // only proceed if an efficient address has been found
+                    if score.is_none() && !nested {
+                        return;
+                    }
 
-                // only proceed if an efficient address has been found
-                if reward_amount.is_none() {
-                    return;
+                    // get the full salt used to create the address
+                    let mut full_salt = *salt_template;
+                    full_salt[counter.clone()].copy_from_slice(salt_incremented_segment);
+
+                    // derive the other addresses, and only proceed if they are
+                    // all efficient too
+                    let others =
+                        children::describe(&config.children, &address, config.scorer.as_ref())
+                            .zip(deployment::describe(&config, &full_salt))
+                            .map(|(children, deployments)| children + &deployments);
+                    let Some(others) = others else {
+                        return;
+                    };
+                    let full_salt = format!("0x{}", hex::encode(full_salt));
+
+                    // display the salt and the address.
+                    let score = score.map_or(Cow::Borrowed("0"), |score| score.label);
+                    let output = format!("{full_salt} => {address} => {score}{others}");
+                    println!(
+                        "{}{full_salt} => {} => {score}{others}",
+                        labels[i],
+                        shown_address(&config, &address)
+                    );
+
+                    // create a lock on the file before writing
+                    files[i].lock_exclusive().expect("Couldn't lock file.");
+
+                    // write the result to file
+                    writeln!(&files[i], "{output}").unwrap_or_else(|_| {
+                        panic!("Couldn't write to `{}` file.", target.output.display())
+                    });
+
+                    // release the file lock
+                    FileExt::unlock(&files[i]).expect("Couldn't unlock file.");
+
+                    // drop the target once it has reached its quality
+                    if let Some(until) = target.until {
+                        if config.scorer.reaches(&address, until)
+                            && !done[i].swap(true, Ordering::Relaxed)
+                        {
+                            println!(
+                                "{}reached {} leading or {} total zeroes; dropping it from the \
+                                 search",
+                                labels[i], until.leading_zeroes, until.total_zeroes
+                            );
+                        }
+                    }
+                };
+
+                // try the salt against every target still being searched for,
+                // cutting the batch short once there are none
+                let mut searching = false;
+                for (i, done) in done.iter().enumerate() {
+                    if !done.load(Ordering::Relaxed) {
+                        searching = true;
+                        search(i);
+                    }
                 }
+                match searching {
+                    true => Ok(()),
+                    false => Err(()),
+                }
+            });
 
-                // get the full salt used to create the address
-                let header_hex_string = hex::encode(header);
-                let body_hex_string = hex::encode(salt_incremented_segment);
-                let full_salt = format!("0x{}{}", &header_hex_string[42..], &body_hex_string);
-
-                // display the salt and the address.
-                let output = format!(
-                    "{full_salt} => {address} => {}",
-                    reward_amount.unwrap_or("0")
-                );
-                println!("{output}");
-
-                // create a lock on the file before writing
-                file.lock_exclusive().expect("Couldn't lock file.");
-
-                // write the result to file
-                writeln!(&file, "{output}")
-                    .expect("Couldn't write to `efficient_addresses.txt` file.");
+        // stop once every target has reached its quality
+        if finished.is_err() {
+            println!("Every target has reached its quality");
+            return Ok(());
+        }
 
-                // release the file lock
-                file.unlock().expect("Couldn't unlock file.")
-            });
+        // without random bytes, every salt has been tried
+        if config.salt_layout.random_len() == 0 {
+            println!("Tried all {} salts", max_counter as u128 + 1);
+            return Ok(());
+        }
     }
 }
 
@@ -243,30 +1000,38 @@ pub fn cpu(config: Config) -> Result<(), Box<dyn Error>> {
 /// meet or exceed the threshold will not be returned. Default threshold values
 /// are three leading zeroes or five total zeroes.
 ///
-/// The 32-byte salt is constructed as follows:
+/// The 32-byte salt is constructed according to the salt layout, by default:
 ///   - the 20-byte calling address (to prevent frontrunning)
 ///   - a random 4-byte segment (to prevent collisions with other runs)
-///   - a 4-byte segment unique to each work group running in parallel
-///   - a 4-byte nonce segment (incrementally stepped through during the run)
+///   - an 8-byte counter segment, made up of a 4-byte segment unique to each
+///     work item running in parallel followed by a 4-byte nonce segment
+///     (incrementally stepped through during the run)
 ///
 /// When a salt that will result in the creation of a gas-efficient contract
-/// address is found, it will be appended to `efficient_addresses.txt` along
-/// with the resultant address and the "value" (i.e. approximate rarity) of the
-/// resultant address.
+/// address is found, it will be appended to the output file (by default
+/// `efficient_addresses.txt`) along with the resultant address and the "value"
+/// (i.e. approximate rarity) of the resultant address.
 ///
 /// This method is still highly experimental and could almost certainly use
 /// further optimization - contributions are more than welcome!
 pub fn gpu(config: Config) -> ocl::Result<()> {
+    let gpu_device = config.gpu_device.unwrap_or(0);
     println!(
-        "Setting up experimental OpenCL miner using device {}...",
-        config.gpu_device
+        "Setting up experimental OpenCL miner using device {gpu_device} and init code hash 0x{}...",
+        hex::encode(config.init_code_hash)
     );
 
+    // warn if the salts found can be frontrun
+    warn_on_unbound_salt(&config);
+
+    // warn if the artifact has changed since it was last searched with
+    results::warn_on_artifact_change(&config);
+
     // (create if necessary) and open a file where found salts will be written
-    let file = output_file();
+    let file = output_file(&config.output);
 
-    // create object for computing rewards (relative rarity) for a given address
-    let rewards = Reward::new();
+    // record the parameters of this run alongside the results
+    write_run_header(&file, &config.output, &RunHeader::new(&config));
 
     // track how many addresses have been found and information about them
     let mut found: u64 = 0;
@@ -275,29 +1040,8 @@ pub fn gpu(config: Config) -> ocl::Result<()> {
     // set up a controller for terminal output
     let term = Term::stdout();
 
-    // set up a platform to use
-    let platform = Platform::new(ocl::core::default_platform()?);
-
-    // set up the device to use
-    let device = Device::by_idx_wrap(platform, config.gpu_device as usize)?;
-
-    // set up the context to use
-    let context = Context::builder()
-        .platform(platform)
-        .devices(device)
-        .build()?;
-
-    // set up the program to use
-    let program = Program::builder()
-        .devices(device)
-        .src(mk_kernel_src(&config))
-        .build(&context)?;
-
-    // set up the queue to use
-    let queue = Queue::new(&context, device, None)?;
-
     // set up the "proqueue" (or amalgamation of various elements) to use
-    let ocl_pq = ProQue::new(context, queue, program, Some(WORK_SIZE));
+    let ocl_pq = mk_pro_que(&config, gpu_device)?;
 
     // create a random number generator
     let mut rng = thread_rng();
@@ -320,21 +1064,22 @@ pub fn gpu(config: Config) -> ocl::Result<()> {
 
     // begin searching for addresses
     loop {
-        // construct the 4-byte message to hash, leaving last 8 of salt empty
-        let salt = FixedBytes::<4>::random();
+        // construct the random bytes of the salt, leaving the counter empty
+        // (at least one byte is needed to build the buffer)
+        let mut random = vec![0; config.salt_layout.random_len().max(1)];
+        rng.fill(&mut random[..]);
 
         // build a corresponding buffer for passing the message to the kernel
         let message_buffer = Buffer::builder()
             .queue(ocl_pq.queue().clone())
             .flags(MemFlags::new().read_only())
-            .len(4)
-            .copy_host_slice(&salt[..])
+            .len(random.len())
+            .copy_host_slice(&random)
             .build()?;
 
-        // reset nonce & create a buffer to view it in little-endian
+        // reset nonce
         // for more uniformly distributed nonces, we shall initialize it to a random value
         let mut nonce: [u32; 1] = rng.gen();
-        let mut view_buf = [0; 8];
 
         // build a corresponding buffer for passing the nonce to the kernel
         let mut nonce_buffer = Buffer::builder()
@@ -397,8 +1142,14 @@ pub fn gpu(config: Config) -> ocl::Result<()> {
                     rate = 1.0 / total_runtime;
                 }
 
-                // fill the buffer for viewing the properly-formatted nonce
-                LittleEndian::write_u64(&mut view_buf, (nonce[0] as u64) << 32);
+                // show the salt with the work item id segment of the counter unknown
+                let salt = config.salt_layout.salt(&random, (nonce[0] as u64) << 32);
+                let counter = config.salt_layout.counter_range();
+                let search_space = format!(
+                    "{}xxxxxxxx{}",
+                    hex::encode(&salt[..counter.start]),
+                    hex::encode(&salt[counter.start + 4..])
+                );
 
                 // calculate the terminal height, defaulting to a height of ten rows
                 let height = terminal_size().map(|(_w, Height(h))| h).unwrap_or(10);
@@ -424,16 +1175,19 @@ pub fn gpu(config: Config) -> ocl::Result<()> {
 
                 // display information about the current search criteria
                 term.write_line(&format!(
-                    "current search space: {}xxxxxxxx{:08x}\t\t\
+                    "current search space: {}\t\t\
                      threshold: {} leading or {} total zeroes",
-                    hex::encode(salt),
-                    BigEndian::read_u64(&view_buf),
-                    config.leading_zeroes_threshold,
-                    config.total_zeroes_threshold
+                    search_space, config.leading_zeroes_threshold, config.total_zeroes_threshold
+                ))?;
+
+                // display the init code hash that found salts are valid for
+                term.write_line(&format!(
+                    "init code hash: 0x{}",
+                    hex::encode(config.init_code_hash)
                 ))?;
 
                 // display recently found solutions based on terminal height
-                let rows = if height < 5 { 1 } else { height as usize - 4 };
+                let rows = if height < 6 { 1 } else { height as usize - 5 };
                 let last_rows: Vec<String> = found_list.iter().cloned().rev().take(rows).collect();
                 let ordered: Vec<String> = last_rows.iter().cloned().rev().collect();
                 let recently_found = &ordered.join("\n");
@@ -484,73 +1238,495 @@ pub fn gpu(config: Config) -> ocl::Result<()> {
                 continue;
             }
 
-            let solution = solution.to_le_bytes();
+            // the solution is the counter: the work item id, then the nonce
+            let salt = config.salt_layout.salt(&random, solution);
 
-            let mut solution_message = [0; 85];
-            solution_message[0] = CONTROL_CHARACTER;
-            solution_message[1..21].copy_from_slice(&config.factory_address);
-            solution_message[21..41].copy_from_slice(&config.calling_address);
-            solution_message[41..45].copy_from_slice(&salt[..]);
-            solution_message[45..53].copy_from_slice(&solution);
-            solution_message[53..].copy_from_slice(&config.init_code_hash);
+            // get the address that results from the salt
+            let address = derive_address(&config, &salt);
 
-            // create new hash object
-            let mut hash = Keccak::v256();
+            // count total and leading zero bytes
+            let (leading, total) = children::zero_bytes(&address);
 
-            // update with header
-            hash.update(&solution_message);
+            let score = scorer::label(config.scorer.as_ref(), &address);
+            let output = format!("0x{} => {} => {}", hex::encode(salt), address, score);
 
-            // hash the payload and get the result
-            let mut res: [u8; 32] = [0; 32];
-            hash.finalize(&mut res);
+            let show = format!(
+                "0x{} => {} => {score} ({leading} / {total})",
+                hex::encode(salt),
+                shown_address(&config, &address)
+            );
+            found_list.push(show.to_string());
 
-            // get the address that results from the hash
-            let address = <&Address>::try_from(&res[12..]).unwrap();
+            file.lock_exclusive().expect("Couldn't lock file.");
 
-            // count total and leading zero bytes
-            let mut total = 0;
-            let mut leading = 0;
-            for (i, &b) in address.iter().enumerate() {
-                if b == 0 {
-                    total += 1;
-                } else if leading == 0 {
-                    // set leading on finding non-zero byte
-                    leading = i;
+            writeln!(&file, "{output}").unwrap_or_else(|_| {
+                panic!("Couldn't write to `{}` file.", config.output.display())
+            });
+
+            FileExt::unlock(&file).expect("Couldn't unlock file.");
+            found += 1;
+        }
+    }
+}
+
+/// Given a Config object and a hex-encoded 32-byte salt, compute the address
+/// that the factory will deploy the contract to (see `Derivation`), along with the number of
+/// leading and total zero bytes and the "value" of the resultant address.
+pub fn verify(config: Config, salt: &str) -> Result<(), Box<dyn Error>> {
+    let salt: [u8; 32] = parse::fixed(Argument::Salt, salt)?;
+
+    println!("init code hash: 0x{}", hex::encode(config.init_code_hash));
+    match config.derivation {
+        Derivation::Create2 => {}
+        Derivation::Create3 => {
+            println!("deriving the address deployed by the CREATE3 proxy with that init code hash")
+        }
+        Derivation::ZkSync {
+            constructor_input_hash,
+        } => println!(
+            "deriving the zkSync Era address with that as the versioned bytecode hash and \
+             constructor input hash 0x{}",
+            hex::encode(constructor_input_hash)
+        ),
+    }
+    if let Some(guard) = config.createx {
+        match CreatexGuard::of_salt(&salt, &config.calling_address) {
+            Some(salt_guard) if salt_guard == guard => println!(
+                "CreateX guards this salt with {guard}; pass it to {}",
+                createx_function(&config)
+            ),
+            Some(salt_guard) => {
+                return Err(format!(
+                    "CreateX would guard this salt with {salt_guard} rather than {guard}"
+                )
+                .into())
+            }
+            None => return Err("CreateX would revert with InvalidSalt for this salt".into()),
+        }
+    }
+    if let Some(hashed_salt) = &config.hashed_salt {
+        println!(
+            "CREATE2 salt (hashed from {hashed_salt}): 0x{}",
+            hex::encode(hashed_salt.hash(&salt))
+        );
+    }
+
+    if let Some(preset) = config.factory_preset {
+        let create3 = config.derivation == Derivation::Create3;
+        match &config.init_code {
+            Some(init_code) => println!(
+                "calldata for the {preset} factory: 0x{}",
+                hex::encode(preset.calldata(create3, &salt, init_code))
+            ),
+            None => println!(
+                "call the {preset} factory with this salt{}",
+                preset.function(create3).map_or(
+                    " followed by the init code".to_string(),
+                    |function| format!(" via {function}")
+                )
+            ),
+        }
+    }
+
+    let address = derive_address(&config, &salt);
+
+    // count total and leading zero bytes
+    let (leading, total) = children::zero_bytes(&address);
+
+    let score = scorer::label(config.scorer.as_ref(), &address);
+
+    println!(
+        "0x{} => {} => {score} ({leading} / {total})",
+        hex::encode(salt),
+        shown_address(&config, &address)
+    );
+
+    if !config.scorer.reaches(&address, config.threshold()) {
+        println!(
+            "address does not meet the threshold of {} leading or {} total zeroes",
+            config.leading_zeroes_threshold, config.total_zeroes_threshold
+        );
+    }
+
+    // the other deployments of the contract
+    for (i, deployment) in config.deployments.iter().enumerate() {
+        let salt = deployment.salt(&salt, &config.salt_layout, &config.calling_address);
+        let address = deployment.address(&salt, config.hashed_salt.as_ref());
+        let (leading, total) = children::zero_bytes(&address);
+        let score = scorer::label(config.scorer.as_ref(), &address);
+
+        println!(
+            "deployment {} => 0x{} => {address} => {score} ({leading} / {total})",
+            i + 1,
+            hex::encode(salt)
+        );
+        if !config.scorer.reaches(&address, config.threshold()) {
+            println!(
+                "deployment does not meet the threshold of {} leading or {} total zeroes",
+                config.leading_zeroes_threshold, config.total_zeroes_threshold
+            );
+        }
+    }
+
+    // the children deployed by the contract via CREATE
+    for child in &config.children {
+        let address = child.address(&address);
+        let (leading, total) = children::zero_bytes(&address);
+        let score = scorer::label(config.scorer.as_ref(), &address);
+
+        println!(
+            "nonce {} => {address} => {score} ({leading} / {total})",
+            child.nonce
+        );
+        if !config.scorer.reaches(&address, child.threshold()) {
+            println!(
+                "child does not meet the threshold of {} leading or {} total zeroes",
+                child.leading_zeroes_threshold, child.total_zeroes_threshold
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Given a Config object, typically with init code read from a bytecode file
+/// or build artifact, check that the salts in its output file are still valid.
+/// The init code hash is compared with the hash recorded by each run in the
+/// output file, and the address of every result is derived again with the
+/// current init code hash and the factory, hashed salt and CREATE2 prefix
+/// recorded by its run, then compared with the recorded address. A result is
+/// only stale, then, if the init code hash has changed since its run. Every
+/// result is reported as either still deriving correctly or as stale, and an
+/// error is returned if any are stale. The results of runs that derived
+/// addresses differently (e.g. via CREATE3 when checking CREATE2 addresses) are
+/// skipped, as are those of runs whose header can't be read.
+pub fn check(config: Config) -> Result<(), Box<dyn Error>> {
+    let current = RunHeader::new(&config);
+    println!("init code hash: {}", current.init_code_hash);
+
+    let Ok(contents) = std::fs::read_to_string(&config.output) else {
+        return Err(format!("could not read `{}` file", config.output.display()).into());
+    };
+
+    // results before the first run header are checked with the current settings
+    let mut settings = Ok(RunSettings::new(&config));
+
+    let mut valid = 0;
+    let mut stale = 0;
+    let mut skipped = 0;
+    for (i, line) in contents.lines().enumerate() {
+        let line_number = i + 1;
+
+        if let Some(header) = RunHeader::parse(line) {
+            settings = header.settings(&config);
+            match &settings {
+                Err(reason) => println!("line {line_number}: {reason}, skipping its results"),
+                Ok(_) if header.init_code_hash == current.init_code_hash => {
+                    println!("line {line_number}: run recorded the same init code hash")
                 }
+                Ok(_) => println!(
+                    "line {line_number}: run recorded a different init code hash ({})",
+                    header.init_code_hash
+                ),
             }
+            continue;
+        }
 
-            let key = leading * 20 + total;
-            let reward = rewards.get(&key).unwrap_or("0");
-            let output = format!(
-                "0x{}{}{} => {} => {}",
-                hex::encode(config.calling_address),
-                hex::encode(salt),
-                hex::encode(solution),
-                address,
-                reward,
+        let Some(result) = ResultLine::parse(line) else {
+            continue;
+        };
+
+        let Ok(settings) = &settings else {
+            skipped += 1;
+            continue;
+        };
+
+        let address = settings.derive_address(&config.init_code_hash, &result.salt);
+        if address == result.address {
+            println!(
+                "line {line_number}: 0x{} => {} still derives correctly",
+                hex::encode(result.salt),
+                result.address
             );
+            valid += 1;
+        } else {
+            println!(
+                "line {line_number}: 0x{} => {} is stale (now derives {address})",
+                hex::encode(result.salt),
+                result.address
+            );
+            stale += 1;
+        }
+    }
 
-            let show = format!("{output} ({leading} / {total})");
-            found_list.push(show.to_string());
+    println!("{valid} results still derive correctly, {stale} are stale, {skipped} were skipped");
 
-            file.lock_exclusive().expect("Couldn't lock file.");
+    if stale > 0 {
+        return Err(format!("{stale} results in `{}` are stale", config.output.display()).into());
+    }
 
-            writeln!(&file, "{output}").expect("Couldn't write to `efficient_addresses.txt` file.");
+    Ok(())
+}
 
-            file.unlock().expect("Couldn't unlock file.");
-            found += 1;
+/// Given a CloneConfig object, print the init code of the minimal proxy clone
+/// and its hash, which can be passed to a search with --init-code-hash (or
+/// built by the search itself with --clone).
+pub fn print_init_code(config: CloneConfig) {
+    let init_code = config.init_code();
+
+    println!("init code: 0x{}", hex::encode(&init_code));
+    println!("init code hash: 0x{}", hex::encode(keccak256(&init_code)));
+}
+
+/// List every OpenCL platform along with the index of each of its devices,
+/// which is the value expected by the `--gpu-device` argument. Devices are
+/// always selected from the default platform.
+pub fn devices() -> ocl::Result<()> {
+    let default_platform = Platform::new(ocl::core::default_platform()?);
+
+    for (i, platform) in Platform::list().into_iter().enumerate() {
+        let marker = if platform.as_core() == default_platform.as_core() {
+            " (default)"
+        } else {
+            ""
+        };
+        println!("platform {i}: {}{marker}", platform.name()?);
+
+        for (j, device) in Device::list_all(platform)?.into_iter().enumerate() {
+            println!("  device {j}: {} ({})", device.name()?, device.vendor()?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Measure how many addresses per second can be derived on the CPU, or on the
+/// given OpenCL device, over the given number of seconds. Nothing is written
+/// to the output file.
+pub fn bench(gpu_device: Option<u8>, seconds: u64) -> Result<(), Box<dyn Error>> {
+    // thresholds that will never be met, so that the kernel never reports back
+    let config = Config {
+        factory_address: [0; 20],
+        calling_address: [0; 20],
+        init_code_hash: [0; 32],
+        init_code: None,
+        artifact: None,
+        salt_layout: SaltLayout::parse(DEFAULT_SALT_LAYOUT, &[0; 20])?,
+        hashed_salt: None,
+        derivation: Derivation::Create2,
+        createx: None,
+        factory_preset: None,
+        create2_prefix: CONTROL_CHARACTER,
+        tron: false,
+        children: vec![],
+        deployments: vec![],
+        targets: vec![],
+        until: None,
+        gpu_device,
+        leading_zeroes_threshold: 20,
+        total_zeroes_threshold: 255,
+        scorer: Arc::new(Reward::new()),
+        output: PathBuf::from(DEFAULT_OUTPUT),
+    };
+
+    let duration = Duration::from_secs(seconds);
+    let start = Instant::now();
+    let mut attempts: u64 = 0;
+
+    if let Some(gpu_device) = gpu_device {
+        println!("Benchmarking OpenCL device {gpu_device} for {seconds} seconds...");
+
+        let ocl_pq = mk_pro_que(&config, gpu_device)?;
+
+        let message_buffer = Buffer::builder()
+            .queue(ocl_pq.queue().clone())
+            .flags(MemFlags::new().read_only())
+            .len(4)
+            .copy_host_slice(&[0u8; 4])
+            .build()?;
+        let nonce_buffer = Buffer::builder()
+            .queue(ocl_pq.queue().clone())
+            .flags(MemFlags::new().read_only())
+            .len(1)
+            .copy_host_slice(&[0u32])
+            .build()?;
+        let mut solutions: Vec<u64> = vec![0; 1];
+        let solutions_buffer = Buffer::builder()
+            .queue(ocl_pq.queue().clone())
+            .flags(MemFlags::new().write_only())
+            .len(1)
+            .copy_host_slice(&solutions)
+            .build()?;
+
+        let kern = ocl_pq
+            .kernel_builder("hashMessage")
+            .arg_named("message", Some(&message_buffer))
+            .arg_named("nonce", Some(&nonce_buffer))
+            .arg_named("solutions", Some(&solutions_buffer))
+            .build()?;
+
+        while start.elapsed() < duration {
+            unsafe { kern.enq()? };
+
+            // reading the solutions blocks until the work has completed
+            solutions_buffer.read(&mut solutions).enq()?;
+            attempts += WORK_SIZE as u64;
+        }
+    } else {
+        println!("Benchmarking CPU for {seconds} seconds...");
+
+        let mut header = [0; 47];
+        header[0] = CONTROL_CHARACTER;
+
+        let mut hash_header = Keccak::v256();
+        hash_header.update(&header);
+
+        // check the elapsed time after every batch of (2^20) attempts
+        let batch_size: u64 = 0x100000;
+        while start.elapsed() < duration {
+            (0..batch_size).into_par_iter().for_each(|salt| {
+                let salt = salt.to_le_bytes();
+
+                let mut hash = hash_header.clone();
+                hash.update(&salt[..6]);
+                hash.update(&config.init_code_hash);
+
+                let mut res: [u8; 32] = [0; 32];
+                hash.finalize(&mut res);
+                black_box(res);
+            });
+            attempts += batch_size;
         }
     }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{} attempts in {elapsed:.2} seconds: {:.2} million attempts per second",
+        attempts.separated_string(),
+        attempts as f64 / elapsed / 1_000_000.0
+    );
+
+    Ok(())
+}
+
+/// Compute the address that the factory in the Config object will deploy the
+/// contract to when supplied with the given salt (see `SaltHash` and
+/// `Derivation`).
+fn derive_address(config: &Config, salt: &[u8; 32]) -> Address {
+    RunSettings::new(config).derive_address(&config.init_code_hash, salt)
+}
+
+/// The address as shown on the terminal, followed by its base58 form when
+/// searching for Tron. Output files always hold the hex address.
+fn shown_address(config: &Config, address: &Address) -> String {
+    match config.tron {
+        true => format!("{address} ({})", tron::base58_address(address)),
+        false => address.to_string(),
+    }
+}
+
+/// The CreateX function that deploys with the salts of the search.
+fn createx_function(config: &Config) -> &'static str {
+    match config.derivation {
+        Derivation::Create2 | Derivation::ZkSync { .. } => "deployCreate2(bytes32,bytes)",
+        Derivation::Create3 => "deployCreate3(bytes32,bytes)",
+    }
+}
+
+/// Warn, prominently, if the salts being searched for are not bound to a
+/// caller: anyone who sees such a salt in a pending deployment can submit it
+/// first and take the address.
+fn warn_on_unbound_salt(config: &Config) {
+    // factories that don't check the caller can't bind salts to it, even if
+    // the salts begin with it
+    let checks_caller = config
+        .factory_preset
+        .map_or(true, |preset| preset.binds_caller());
+    if checks_caller
+        && config.calling_address != [0; 20]
+        && config.salt_layout.is_bound_to(&config.calling_address)
+    {
+        return;
+    }
+
+    // a factory that hashes other data into the salt (like the initializer of
+    // a Safe) binds the salt to that data instead, although the chain id that
+    // CreateX may hash into it doesn't
+    let hashes_data = config.hashed_salt.as_ref().is_some_and(|hashed_salt| {
+        !hashed_salt.prefix().is_empty() || !hashed_salt.suffix().is_empty()
+    });
+    if hashes_data && config.createx.is_none() {
+        return;
+    }
+
+    let rule = "=".repeat(79);
+    eprintln!(
+        "{rule}\n\
+         WARNING: the salts being searched for are not bound to a caller, so they are\n\
+         NOT protected against frontrunning. Anyone who sees a deployment using one\n\
+         of these salts in the mempool can copy it and deploy to the address first.\n\
+         Submit such deployments through a private transaction relay.\n\
+         {rule}"
+    );
+}
+
+/// Set up the OpenCL platform, device, context, program and queue for the
+/// given device, compiling the kernel with the values from the Config object.
+fn mk_pro_que(config: &Config, gpu_device: u8) -> ocl::Result<ProQue> {
+    // set up a platform to use
+    let platform = Platform::new(ocl::core::default_platform()?);
+
+    // set up the device to use
+    let device = Device::by_idx_wrap(platform, gpu_device as usize)?;
+
+    // set up the context to use
+    let context = Context::builder()
+        .platform(platform)
+        .devices(device)
+        .build()?;
+
+    // set up the program to use
+    let program = Program::builder()
+        .devices(device)
+        .src(mk_kernel_src(config))
+        .build(&context)?;
+
+    // set up the queue to use
+    let queue = Queue::new(&context, device, None)?;
+
+    // set up the "proqueue" (or amalgamation of various elements) to use
+    Ok(ProQue::new(context, queue, program, Some(WORK_SIZE)))
 }
 
 #[track_caller]
-fn output_file() -> File {
+fn output_file(path: &Path) -> File {
     OpenOptions::new()
         .append(true)
         .create(true)
         .read(true)
-        .open("efficient_addresses.txt")
-        .expect("Could not create or open `efficient_addresses.txt` file.")
+        .open(path)
+        .unwrap_or_else(|_| panic!("Could not create or open `{}` file.", path.display()))
+}
+
+/// Append a comment line to the output file recording the parameters that the
+/// salts found during this run are valid for (see `RunHeader`).
+fn write_run_header(mut file: &File, path: &Path, header: &RunHeader) {
+    file.lock_exclusive().expect("Couldn't lock file.");
+
+    writeln!(file, "{header}")
+        .unwrap_or_else(|_| panic!("Couldn't write to `{}` file.", path.display()));
+
+    FileExt::unlock(file).expect("Couldn't unlock file.");
+}
+
+/// Compute the keccak-256 hash of the given bytes.
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hash = Keccak::v256();
+    hash.update(data);
+
+    let mut res: [u8; 32] = [0; 32];
+    hash.finalize(&mut res);
+    res
 }
 
 /// Creates the OpenCL kernel source code by populating the template with the
@@ -558,13 +1734,33 @@ fn output_file() -> File {
 fn mk_kernel_src(config: &Config) -> String {
     let mut src = String::with_capacity(2048 + KERNEL_SRC.len());
 
+    writeln!(src, "#define S_0 {}u", config.create2_prefix).unwrap();
+
     let factory = config.factory_address.iter();
-    let caller = config.calling_address.iter();
     let hash = config.init_code_hash.iter();
     let hash = hash.enumerate().map(|(i, x)| (i + 52, x));
-    for (i, x) in factory.chain(caller).enumerate().chain(hash) {
+    for (i, x) in factory.enumerate().chain(hash) {
         writeln!(src, "#define S_{} {}u", i + 1, x).unwrap();
     }
+
+    // each salt byte is either fixed, a random byte from the message, or a
+    // byte of the counter (made up of the work item id and the nonce)
+    let mut i = 21;
+    let mut message_index = 0;
+    for region in config.salt_layout.regions() {
+        for j in 0..region.len() {
+            match region {
+                SaltRegion::Fixed(fixed) => writeln!(src, "#define S_{i} {}u", fixed[j]),
+                SaltRegion::Random(_) => {
+                    message_index += 1;
+                    writeln!(src, "#define S_{i} d_message[{}]", message_index - 1)
+                }
+                SaltRegion::Counter(_) => writeln!(src, "#define S_{i} nonce.uint8_t[{j}]"),
+            }
+            .unwrap();
+            i += 1;
+        }
+    }
     let lz = config.leading_zeroes_threshold;
     writeln!(src, "#define LEADING_ZEROES {lz}").unwrap();
     let tz = config.total_zeroes_threshold;
@@ -574,3 +1770,115 @@ fn mk_kernel_src(config: &Config) -> String {
 
     src
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+
+    pub(crate) const FACTORY: &str = "0x0000000000ffe8b47b3e2130213b802212439497";
+    pub(crate) const CALLER: &str = "0x1111111111111111111111111111111111111111";
+    pub(crate) const INIT_CODE_HASH: &str =
+        "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+    /// The config of a search with the given arguments alone.
+    pub(crate) fn config(args: &[&str]) -> Result<Config, ConfigError> {
+        let cli = Cli::try_parse_from(["create2crunch", "search"].iter().chain(args)).unwrap();
+        let Command::Search(args) = cli.command else {
+            unreachable!()
+        };
+        Config::new(&args)
+    }
+
+    /// The config of a search of `FACTORY` and `INIT_CODE_HASH` with the
+    /// given arguments.
+    pub(crate) fn search_config(args: &[&str]) -> Config {
+        let defaults = ["--factory", FACTORY, "--init-code-hash", INIT_CODE_HASH];
+        config(&[&defaults, args].concat()).unwrap()
+    }
+
+    /// Evaluate the `S_i` defines of the kernel source that make up the salt,
+    /// given the random bytes of the message and the counter held by `nonce`.
+    fn kernel_salt(src: &str, random: &[u8], counter: u64) -> [u8; 32] {
+        let mut salt = [0; 32];
+        for (i, byte) in salt.iter_mut().enumerate() {
+            let define = format!("#define S_{} ", i + 21);
+            let line = src.lines().find(|line| line.starts_with(&define)).unwrap();
+            let value = &line[define.len()..];
+            *byte = if let Some(fixed) = value.strip_suffix('u') {
+                fixed.parse().unwrap()
+            } else if let Some(k) = value.strip_prefix("d_message[") {
+                random[k.trim_end_matches(']').parse::<usize>().unwrap()]
+            } else if let Some(j) = value.strip_prefix("nonce.uint8_t[") {
+                counter.to_le_bytes()[j.trim_end_matches(']').parse::<usize>().unwrap()]
+            } else {
+                panic!("unexpected salt byte {value}")
+            };
+        }
+        salt
+    }
+
+    #[test]
+    fn default_layout_depends_on_the_device() {
+        let cpu = search_config(&["--caller", CALLER]);
+        assert_eq!(
+            cpu.salt_layout.to_string(),
+            format!("fixed:{CALLER},random:6,counter:6")
+        );
+
+        let gpu = search_config(&["--caller", CALLER, "--gpu-device", "0"]);
+        assert_eq!(
+            gpu.salt_layout.to_string(),
+            format!("fixed:{CALLER},random:4,counter:8")
+        );
+    }
+
+    #[test]
+    fn kernel_builds_the_same_salts_as_the_cpu() {
+        let random: Vec<u8> = (0xa0..0xb8).collect();
+        let counter = 0x8877665544332211;
+        for args in [
+            &["--caller", CALLER][..],
+            &["--unbound-salt", "zero"],
+            &["--unbound-salt", "random"],
+            &[
+                "--caller",
+                CALLER,
+                "--salt-layout",
+                "caller,fixed:0x01,random:3,counter:8",
+            ],
+            &[
+                "--caller",
+                CALLER,
+                "--salt-layout",
+                "counter:8,fixed:0x00ff,random:2,caller",
+            ],
+            &[
+                "--caller",
+                CALLER,
+                "--salt-layout",
+                "random:1,zero:11,random:3,counter:8,random:9",
+            ],
+        ] {
+            let config = search_config(&[args, &["--gpu-device", "0"]].concat());
+            let src = mk_kernel_src(&config);
+
+            assert!(src.starts_with(&format!("#define S_0 {}u\n", CONTROL_CHARACTER)));
+            for (i, byte) in config.factory_address.iter().enumerate() {
+                assert!(src.contains(&format!("#define S_{} {byte}u\n", i + 1)));
+            }
+            for (i, byte) in config.init_code_hash.iter().enumerate() {
+                assert!(src.contains(&format!("#define S_{} {byte}u\n", i + 53)));
+            }
+
+            let random = &random[..config.salt_layout.random_len()];
+            assert_eq!(
+                kernel_salt(&src, random, counter),
+                config.salt_layout.salt(random, counter),
+                "{}",
+                config.salt_layout
+            );
+        }
+    }
+}
@@ -1,20 +1,102 @@
-use create2crunch::Config;
-use std::env;
+use clap::Parser;
+use create2crunch::cli::{Cli, Command, SearchArgs};
+use create2crunch::{CloneConfig, Config, DeployerConfig, MutateConfig, PlanConfig};
 use std::process;
 
 fn main() {
-    let config = Config::new(env::args()).unwrap_or_else(|err| {
-        eprintln!("Failed parsing arguments: {err}");
-        process::exit(1);
-    });
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Search(args) => {
+            let config = config(args);
+
+            if config.gpu_device.is_none() {
+                if let Err(e) = create2crunch::cpu(config) {
+                    eprintln!("CPU application error: {e}");
+                    process::exit(1);
+                }
+            } else if let Err(e) = create2crunch::gpu(config) {
+                eprintln!("GPU application error: {e}");
+                process::exit(1);
+            }
+        }
+        Command::Verify(args) => {
+            let config = config(args.search);
+
+            if let Err(e) = create2crunch::verify(config, &args.salt) {
+                eprintln!("Verification error: {e}");
+                process::exit(1);
+            }
+        }
+        Command::Check(args) => {
+            let config = config(args);
+
+            if let Err(e) = create2crunch::check(config) {
+                eprintln!("Check error: {e}");
+                process::exit(1);
+            }
+        }
+        Command::Deployer(args) => {
+            let config = DeployerConfig::new(&args).unwrap_or_else(|err| {
+                eprintln!("Failed parsing arguments: {err}");
+                process::exit(1);
+            });
 
-    if config.gpu_device == 255 {
-        if let Err(e) = create2crunch::cpu(config) {
-            eprintln!("CPU application error: {e}");
-            process::exit(1);
+            if let Err(e) = create2crunch::deployer(config) {
+                eprintln!("Deployer search error: {e}");
+                process::exit(1);
+            }
         }
-    } else if let Err(e) = create2crunch::gpu(config) {
-        eprintln!("GPU application error: {e}");
+        Command::Plan(args) => {
+            let config = PlanConfig::new(&args).unwrap_or_else(|err| {
+                eprintln!("Failed parsing arguments: {err}");
+                process::exit(1);
+            });
+
+            create2crunch::plan(config);
+        }
+        Command::InitCode(args) => {
+            let config = CloneConfig::new(&args).unwrap_or_else(|err| {
+                eprintln!("Failed parsing arguments: {err}");
+                process::exit(1);
+            });
+
+            create2crunch::print_init_code(config);
+        }
+        Command::Mutate(args) => {
+            let config = MutateConfig::new(&args).unwrap_or_else(|err| {
+                eprintln!("Failed parsing arguments: {err}");
+                process::exit(1);
+            });
+
+            if let Err(e) = create2crunch::mutate(config) {
+                eprintln!("Mutation search error: {e}");
+                process::exit(1);
+            }
+        }
+        Command::Devices => {
+            if let Err(e) = create2crunch::devices() {
+                eprintln!("OpenCL error: {e}");
+                process::exit(1);
+            }
+        }
+        Command::Bench(args) => {
+            if let Err(e) = create2crunch::bench(args.gpu_device, args.seconds) {
+                eprintln!("Benchmark error: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn config(mut args: SearchArgs) -> Config {
+    if let Err(err) = args.apply_profile() {
+        eprintln!("Failed loading profile: {err}");
         process::exit(1);
     }
+
+    Config::new(&args).unwrap_or_else(|err| {
+        eprintln!("Failed parsing arguments: {err}");
+        process::exit(1);
+    })
 }
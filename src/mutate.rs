@@ -0,0 +1,191 @@
+use crate::cli::MutateArgs;
+use crate::derivation::create_address;
+use crate::error::{Argument, ConfigError};
+use crate::{init_code, output_file, parse, Reward, Scorer};
+use alloy_primitives::{hex, Address};
+use fs4::FileExt;
+use rand::{thread_rng, Rng};
+use rayon::prelude::*;
+use std::error::Error;
+use std::io::prelude::*;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Most bytes of the mutable range that are stepped through in each batch,
+/// with any bytes before them chosen at random for each batch.
+const MAX_COUNTER_LEN: usize = 4;
+
+/// Default file that the patched init code is appended to.
+const DEFAULT_MUTATE_OUTPUT: &str = "mutated_init_code.txt";
+
+/// Requires the init code of the contract, the range of its bytes that may be
+/// changed (e.g. an unused constructor argument or trailing data byte), and the
+/// factory and fixed salt that it is deployed with via CREATE2. The address
+/// scored is that of the contract, or with a nonce, that of the contract it
+/// deploys via CREATE with that nonce (as CREATE addresses depend only on the
+/// deployer and its nonce, the init code only changes them through the address
+/// of the deployer). Addresses are scored by `Reward` unless another `Scorer`
+/// is set.
+pub struct MutateConfig {
+    pub init_code: Vec<u8>,
+    pub mutable: Range<usize>,
+    pub factory_address: [u8; 20],
+    pub salt: [u8; 32],
+    pub nonce: Option<u64>,
+    pub output: PathBuf,
+    pub scorer: Arc<dyn Scorer>,
+}
+
+impl MutateConfig {
+    pub fn new(args: &MutateArgs) -> Result<Self, ConfigError> {
+        let init_code = init_code::read(Argument::InitCode, &args.init_code)?;
+        let mutable = parse_range(&args.mutable, init_code.len())?;
+
+        Ok(Self {
+            init_code,
+            mutable,
+            factory_address: parse::address(Argument::Factory, &args.factory)?,
+            salt: parse::fixed(Argument::Salt, &args.salt)?,
+            nonce: args.nonce,
+            output: args
+                .output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_MUTATE_OUTPUT)),
+            scorer: Arc::new(Reward::new()),
+        })
+    }
+}
+
+/// Parse a range of byte offsets into the init code like `100..132` or
+/// `131..=131`.
+fn parse_range(value: &str, len: usize) -> Result<Range<usize>, ConfigError> {
+    let invalid = ConfigError::InvalidMutableRange;
+    let number = |value: &str| {
+        value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| invalid("expected a range of byte offsets like `100..132`"))
+    };
+
+    let range = if let Some((start, end)) = value.split_once("..=") {
+        number(start)?..number(end)?.saturating_add(1)
+    } else if let Some((start, end)) = value.split_once("..") {
+        number(start)?..number(end)?
+    } else {
+        return Err(invalid("expected a range of byte offsets like `100..132`"));
+    };
+
+    if range.is_empty() {
+        return Err(invalid("the range of bytes is empty"));
+    }
+    if range.end > len {
+        return Err(invalid(
+            "the range of bytes extends past the end of the init code",
+        ));
+    }
+    Ok(range)
+}
+
+/// Given a MutateConfig object, search for init code that the factory deploys
+/// to a gas-efficient address with its fixed salt, by changing only the
+/// mutable range of bytes. The last bytes of the range (up to four) are stepped
+/// through in each batch, and any bytes before them are chosen at random for
+/// each batch; ranges of up to four bytes are tried exhaustively, once.
+///
+/// When init code that results in a gas-efficient address is found, the
+/// mutable bytes, the resultant address and the "value" (i.e. approximate
+/// rarity) of the resultant address are printed, and are appended to the
+/// output file (by default `mutated_init_code.txt`) along with the patched
+/// init code, which deploys to that address.
+pub fn mutate(config: MutateConfig) -> Result<(), Box<dyn Error>> {
+    println!(
+        "Searching on the CPU for init code with bytes {}..{} changed...",
+        config.mutable.start, config.mutable.end
+    );
+    if let Some(nonce) = config.nonce {
+        println!("Scoring the address that the contract deploys via CREATE with nonce {nonce}");
+    }
+
+    // (create if necessary) and open a file where patched init code will be
+    // written
+    let file = output_file(&config.output);
+
+    let factory = Address::from(config.factory_address);
+    let counter_len = config.mutable.len().min(MAX_COUNTER_LEN);
+    let random_len = config.mutable.len() - counter_len;
+    let max_counter = u64::MAX >> (64 - 8 * counter_len);
+    let suffix = &config.init_code[config.mutable.end..];
+
+    // begin searching for init code
+    loop {
+        let mut random = vec![0; random_len];
+        thread_rng().fill(&mut random[..]);
+
+        // the init code up to the counter is hashed once per batch
+        let mut header = Keccak::v256();
+        header.update(&config.init_code[..config.mutable.start]);
+        header.update(&random);
+
+        (0..=max_counter)
+            .into_par_iter() // parallelization
+            .for_each(|counter| {
+                let counter = counter.to_be_bytes();
+                let counter = &counter[8 - counter_len..];
+
+                // finish hashing the init code
+                let mut hash = header.clone();
+                hash.update(counter);
+                hash.update(suffix);
+                let mut init_code_hash = [0; 32];
+                hash.finalize(&mut init_code_hash);
+
+                // get the address that results from the init code hash
+                let address = factory.create2(config.salt, init_code_hash);
+                let address = match config.nonce {
+                    Some(nonce) => create_address(address.as_slice(), nonce),
+                    None => address,
+                };
+
+                // score the address, only proceeding if an efficient address
+                // has been found
+                let Some(score) = config.scorer.score(&address) else {
+                    return;
+                };
+                let reward = score.label;
+
+                // patch the init code with the mutable bytes
+                let mut mutable = random.clone();
+                mutable.extend_from_slice(counter);
+                let mut init_code = config.init_code.clone();
+                init_code[config.mutable.clone()].copy_from_slice(&mutable);
+
+                // display the mutable bytes and the address
+                let mutable = format!("0x{}", hex::encode(mutable));
+                println!("{mutable} => {address} => {reward}");
+
+                let output = format!(
+                    "{mutable} => {address} => {reward} => 0x{}",
+                    hex::encode(init_code)
+                );
+
+                // create a lock on the file before writing
+                file.lock_exclusive().expect("Couldn't lock file.");
+
+                // write the result to file
+                writeln!(&file, "{output}").unwrap_or_else(|_| {
+                    panic!("Couldn't write to `{}` file.", config.output.display())
+                });
+
+                // release the file lock
+                FileExt::unlock(&file).expect("Couldn't unlock file.");
+            });
+
+        // without random bytes, every mutation has been tried
+        if random_len == 0 {
+            println!("Tried all {} mutations", max_counter as u128 + 1);
+            return Ok(());
+        }
+    }
+}
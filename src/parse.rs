@@ -0,0 +1,44 @@
+use crate::error::{Argument, ConfigError};
+use alloy_primitives::{hex, Address};
+
+/// Decode a hex-encoded argument. The `0x` prefix is optional for every hex
+/// argument.
+pub(crate) fn hex(argument: Argument, value: &str) -> Result<Vec<u8>, ConfigError> {
+    hex::decode(value.trim()).map_err(|_| ConfigError::InvalidHex(argument))
+}
+
+/// Decode a hex-encoded argument of exactly `N` bytes.
+pub(crate) fn fixed<const N: usize>(
+    argument: Argument,
+    value: &str,
+) -> Result<[u8; N], ConfigError> {
+    let bytes = hex(argument, value)?;
+    let actual = bytes.len();
+
+    bytes.try_into().map_err(|_| ConfigError::InvalidLength {
+        argument,
+        expected: N,
+        actual,
+    })
+}
+
+/// Decode a hex-encoded address. All-lowercase and all-uppercase addresses are
+/// accepted as-is, while mixed-case addresses must match their EIP-55
+/// checksum.
+pub(crate) fn address(argument: Argument, value: &str) -> Result<[u8; 20], ConfigError> {
+    let bytes = fixed::<20>(argument, value)?;
+
+    let value = value.trim();
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    let has_lowercase = digits.chars().any(|c| c.is_ascii_lowercase());
+    let has_uppercase = digits.chars().any(|c| c.is_ascii_uppercase());
+
+    if has_lowercase && has_uppercase {
+        let expected = Address::from(bytes).to_checksum(None);
+        if expected[2..] != *digits {
+            return Err(ConfigError::InvalidChecksum { argument, expected });
+        }
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,296 @@
+use crate::account::AccountFactory;
+use crate::cli::{AccountArgs, CloneArgs, SafeArgs, SearchArgs};
+use crate::clone::CloneKind;
+use crate::createx::CreatexGuard;
+use crate::factory::FactoryPreset;
+use crate::safe::SafeVersion;
+use crate::salt::UnboundSalt;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A named set of search arguments, read from a `[profiles.<name>]` table of
+/// the config file. Keys are named after the corresponding command line flags:
+///
+/// ```toml
+/// [profiles.token]
+/// factory = "0x0000000000ffe8b47b3e2130213b802212439497" # or `factory_preset = "immutable-create2"`
+/// caller = "0x0000000000000000000000000000000000000000"
+/// artifact = "out/Token.sol/Token.json" # or `init_code` / `init_code_hash` / `create3`
+/// zksync = false
+/// tron = false # or `create2_prefix = "0xff"`
+/// constructor_sig = "constructor(address,uint256)"
+/// constructor_args = ["0x0000000000000000000000000000000000000001", "1000"]
+/// salt_layout = "caller,random:6,counter:6" # or `unbound_salt = "zero"`
+/// hashed_salt = "keccak:0x...,salt" # or `createx = "sender-chain"` and `chain_id = 1`
+/// children = "1,2:2/4" # or `deployments = ["0x...,0x...,0x...,zksync"]`
+/// gpu_device = 0
+/// leading_zeroes = 4
+/// total_zeroes = 6
+/// output = "token_addresses.txt"
+/// until = "4/8"
+/// targets = ["init_code_hash=0x...,output=vault_addresses.txt,until=3/6"]
+/// ```
+///
+/// The arguments of the Safe preset are read from a `safe` table of the
+/// profile, with keys named after the flags without their `safe-` prefix:
+///
+/// ```toml
+/// [profiles.treasury.safe]
+/// version = "1.4.1"
+/// proxy_code = "proxy_creation_code.hex"
+/// owners = ["0x0000000000000000000000000000000000000001"]
+/// threshold = 1
+/// ```
+///
+/// Likewise, the arguments of the smart account presets are read from an
+/// `account` table, with `factory` naming the preset given with `--account`:
+///
+/// ```toml
+/// [profiles.wallet.account]
+/// factory = "kernel"
+/// implementation = "0xd6cedde84be40893d153be9d467cd6ad37875b28"
+/// init_data = "0x..."
+/// ```
+///
+/// and those of the minimal proxy clones from a `clone` table, with `kind`
+/// naming the kind given with `--clone` and `args` the immutable args:
+///
+/// ```toml
+/// [profiles.vault.clone]
+/// kind = "erc1167"
+/// implementation = "0x0000000000000000000000000000000000000001"
+/// args = "0x..."
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub factory: Option<String>,
+    pub factory_preset: Option<FactoryPreset>,
+    pub caller: Option<String>,
+    pub init_code_hash: Option<String>,
+    pub init_code: Option<String>,
+    pub artifact: Option<PathBuf>,
+    pub create3: Option<String>,
+    pub zksync: Option<bool>,
+    pub create2_prefix: Option<String>,
+    pub tron: Option<bool>,
+    pub constructor_sig: Option<String>,
+    pub constructor_args: Option<Vec<String>>,
+    pub salt_layout: Option<String>,
+    pub unbound_salt: Option<UnboundSalt>,
+    pub hashed_salt: Option<String>,
+    pub createx: Option<CreatexGuard>,
+    pub chain_id: Option<u64>,
+    pub children: Option<String>,
+    pub deployments: Option<Vec<String>>,
+    pub gpu_device: Option<u8>,
+    pub leading_zeroes: Option<u8>,
+    pub total_zeroes: Option<u8>,
+    pub output: Option<PathBuf>,
+    pub until: Option<String>,
+    pub targets: Option<Vec<String>>,
+    pub safe: Option<SafeProfile>,
+    pub account: Option<AccountProfile>,
+    pub clone: Option<CloneProfile>,
+}
+
+/// The arguments of the Safe preset, read from the `safe` table of a profile.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SafeProfile {
+    pub version: Option<SafeVersion>,
+    pub proxy_code: Option<String>,
+    pub singleton: Option<String>,
+    pub l2: Option<bool>,
+    pub initializer: Option<String>,
+    pub owners: Option<Vec<String>>,
+    pub threshold: Option<u64>,
+    pub fallback_handler: Option<String>,
+}
+
+/// The arguments of the smart account presets, read from the `account` table
+/// of a profile.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountProfile {
+    pub factory: Option<AccountFactory>,
+    pub implementation: Option<String>,
+    pub owner: Option<String>,
+    pub proxy_code: Option<String>,
+    pub init_data: Option<String>,
+}
+
+/// The arguments of the minimal proxy clones, read from the `clone` table of a
+/// profile.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloneProfile {
+    pub kind: Option<CloneKind>,
+    pub implementation: Option<String>,
+    pub args: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profile {
+    /// Read the profile with the given name from a TOML config file.
+    pub fn load(path: &Path, name: &str) -> Result<Self, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("could not read config file {}: {e}", path.display()))?;
+        let mut file: ProfileFile = toml::from_str(&contents)
+            .map_err(|e| format!("could not parse config file {}: {e}", path.display()))?;
+
+        file.profiles.remove(name).ok_or_else(|| {
+            format!(
+                "no profile named `{name}` in config file {}",
+                path.display()
+            )
+            .into()
+        })
+    }
+}
+
+impl SearchArgs {
+    /// Fill in every argument that was not given on the command line from the
+    /// profile selected with `--profile`. Does nothing if no profile is set.
+    pub fn apply_profile(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(name) = &self.profile else {
+            return Ok(());
+        };
+        let profile = Profile::load(&self.config, name)?;
+
+        // a factory given on the command line replaces the factory preset, and
+        // vice versa
+        if self.factory_preset.is_none() {
+            self.factory = self.factory.take().or(profile.factory);
+        }
+        if self.factory.is_none() {
+            self.factory_preset = self.factory_preset.or(profile.factory_preset);
+        }
+        // an unbound salt given on the command line replaces the caller and
+        // salt layout, and vice versa, and a CreateX guard replaces the salt
+        // layout and unbound salt (and the caller, unless it is permissioned)
+        let unbound_createx = self.createx.is_some_and(|guard| !guard.is_permissioned());
+        if self.unbound_salt.is_none() && !unbound_createx {
+            self.caller = self.caller.take().or(profile.caller);
+        }
+        if self.unbound_salt.is_none() && self.createx.is_none() {
+            self.salt_layout = self.salt_layout.take().or(profile.salt_layout);
+        }
+        if self.caller.is_none() && self.salt_layout.is_none() && self.createx.is_none() {
+            self.unbound_salt = self.unbound_salt.or(profile.unbound_salt);
+        }
+        if self.salt_layout.is_none() && self.unbound_salt.is_none() {
+            self.createx = self.createx.or(profile.createx);
+        }
+        if self.createx.is_some() {
+            self.chain_id = self.chain_id.or(profile.chain_id);
+        }
+        // init code given in any form on the command line (including a CREATE3
+        // proxy or a preset) replaces all of them
+        let init_code_given = self.init_code_hash.is_some()
+            || self.init_code.is_some()
+            || self.artifact.is_some()
+            || self.create3.is_some()
+            || self.safe.version.is_some()
+            || self.account.preset.is_some()
+            || self.clone.kind.is_some();
+        if !init_code_given {
+            self.init_code_hash = profile.init_code_hash;
+            self.init_code = profile.init_code;
+            self.artifact = profile.artifact;
+            self.create3 = profile.create3;
+        }
+        if !self.no_zksync {
+            self.zksync = self.zksync || profile.zksync.unwrap_or_default();
+        }
+        // a CREATE2 prefix given on the command line replaces Tron, and vice
+        // versa
+        if !self.tron {
+            self.create2_prefix = self.create2_prefix.take().or(profile.create2_prefix);
+        }
+        if self.create2_prefix.is_none() && !self.no_tron {
+            self.tron = self.tron || profile.tron.unwrap_or_default();
+        }
+        if self.constructor_sig.is_none() && self.constructor_args.is_empty() {
+            self.constructor_sig = profile.constructor_sig;
+            self.constructor_args = profile.constructor_args.unwrap_or_default();
+        }
+        self.hashed_salt = self.hashed_salt.take().or(profile.hashed_salt);
+        self.children = self.children.take().or(profile.children);
+        if self.deployments.is_empty() {
+            self.deployments = profile.deployments.unwrap_or_default();
+        }
+        if !self.cpu {
+            self.gpu_device = self.gpu_device.or(profile.gpu_device);
+        }
+        self.leading_zeroes = self.leading_zeroes.or(profile.leading_zeroes);
+        self.total_zeroes = self.total_zeroes.or(profile.total_zeroes);
+        self.output = self.output.take().or(profile.output);
+        self.until = self.until.take().or(profile.until);
+        if self.targets.is_empty() {
+            self.targets = profile.targets.unwrap_or_default();
+        }
+
+        // Safe arguments given on the command line replace all of the profile's
+        // (as does init code given on the command line in any other form)
+        let safe_given = self.safe.version.is_some()
+            || self.safe.proxy_code.is_some()
+            || self.safe.singleton.is_some()
+            || self.safe.l2
+            || self.safe.initializer.is_some()
+            || !self.safe.owners.is_empty()
+            || self.safe.threshold.is_some()
+            || self.safe.fallback_handler.is_some();
+        if let (false, false, Some(safe)) = (safe_given, init_code_given, profile.safe) {
+            self.safe = SafeArgs {
+                version: safe.version,
+                proxy_code: safe.proxy_code,
+                singleton: safe.singleton,
+                l2: safe.l2.unwrap_or_default(),
+                initializer: safe.initializer,
+                owners: safe.owners.unwrap_or_default(),
+                threshold: safe.threshold,
+                fallback_handler: safe.fallback_handler,
+            };
+        }
+
+        // as do smart account arguments
+        let account_given = self.account.preset.is_some()
+            || self.account.implementation.is_some()
+            || self.account.owner.is_some()
+            || self.account.proxy_code.is_some()
+            || self.account.init_data.is_some();
+        if let (false, false, Some(account)) = (account_given, init_code_given, profile.account) {
+            self.account = AccountArgs {
+                preset: account.factory,
+                implementation: account.implementation,
+                owner: account.owner,
+                proxy_code: account.proxy_code,
+                init_data: account.init_data,
+            };
+        }
+
+        // as do clone arguments
+        let clone_given = self.clone.kind.is_some()
+            || self.clone.implementation.is_some()
+            || self.clone.immutable_args.is_some();
+        if let (false, false, Some(clone)) = (clone_given, init_code_given, profile.clone) {
+            self.clone = CloneArgs {
+                kind: clone.kind,
+                implementation: clone.implementation,
+                immutable_args: clone.args,
+            };
+        }
+
+        Ok(())
+    }
+}
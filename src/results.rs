@@ -0,0 +1,406 @@
+use crate::error::Argument;
+use crate::target::Target;
+use crate::{parse, Config, Derivation, SaltHash, CONTROL_CHARACTER};
+use alloy_primitives::{hex, Address};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use tiny_keccak::{Hasher, Keccak};
+
+/// The parameters of a search run, recorded as a comment line in the output
+/// file ahead of the salts found during that run, e.g.
+/// `# factory=0x... caller=0x... init_code_hash=0x...`. Addresses that are not
+/// derived via CREATE2 directly are noted with e.g. `derivation=create3`, and
+/// salts that the factory hashes with the preimage template of the hash (e.g.
+/// `hashed_salt=fixed:0x...,salt`), as are CREATE2 prefixes other than `0xff`
+/// (e.g. `create2_prefix=0x41` on Tron). When
+/// the init code was read from a build artifact, the hash of its bytecode
+/// (without constructor arguments) and the path of the artifact follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RunHeader {
+    pub(crate) factory: String,
+    pub(crate) caller: String,
+    pub(crate) init_code_hash: String,
+    pub(crate) hashed_salt: Option<String>,
+    pub(crate) derivation: Option<String>,
+    pub(crate) create2_prefix: Option<String>,
+    pub(crate) bytecode_hash: Option<String>,
+    pub(crate) artifact: Option<String>,
+}
+
+impl RunHeader {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            factory: format!("0x{}", hex::encode(config.factory_address)),
+            caller: format!("0x{}", hex::encode(config.calling_address)),
+            init_code_hash: format!("0x{}", hex::encode(config.init_code_hash)),
+            hashed_salt: config.hashed_salt.as_ref().map(ToString::to_string),
+            derivation: (config.derivation != Derivation::Create2)
+                .then(|| config.derivation.to_string()),
+            create2_prefix: (config.create2_prefix != CONTROL_CHARACTER)
+                .then(|| format!("0x{:02x}", config.create2_prefix)),
+            bytecode_hash: config
+                .artifact
+                .as_ref()
+                .map(|artifact| format!("0x{}", hex::encode(artifact.bytecode_hash))),
+            artifact: config
+                .artifact
+                .as_ref()
+                .map(|artifact| artifact.path.display().to_string()),
+        }
+    }
+
+    /// The run header of another target searched in the same pass, which
+    /// shares everything but the caller and init code hash of the search.
+    pub(crate) fn for_target(config: &Config, target: &Target) -> Self {
+        Self {
+            caller: format!("0x{}", hex::encode(target.calling_address)),
+            init_code_hash: format!("0x{}", hex::encode(target.init_code_hash)),
+            bytecode_hash: None,
+            artifact: None,
+            ..Self::new(config)
+        }
+    }
+
+    /// Parse a run header line, returning `None` for any other line.
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end().strip_prefix("# ")?;
+
+        // the artifact path comes last and may contain spaces
+        let (line, artifact) = match line.split_once(" artifact=") {
+            Some((line, artifact)) => (line, Some(artifact.to_string())),
+            None => (line, None),
+        };
+
+        let mut factory = None;
+        let mut caller = None;
+        let mut init_code_hash = None;
+        let mut hashed_salt = None;
+        let mut derivation = None;
+        let mut create2_prefix = None;
+        let mut bytecode_hash = None;
+        for field in line.split_whitespace() {
+            match field.split_once('=')? {
+                ("factory", value) => factory = Some(value.to_string()),
+                ("caller", value) => caller = Some(value.to_string()),
+                ("init_code_hash", value) => init_code_hash = Some(value.to_string()),
+                ("hashed_salt", value) => hashed_salt = Some(value.to_string()),
+                ("derivation", value) => derivation = Some(value.to_string()),
+                ("create2_prefix", value) => create2_prefix = Some(value.to_string()),
+                ("bytecode_hash", value) => bytecode_hash = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            factory: factory?,
+            caller: caller?,
+            init_code_hash: init_code_hash?,
+            hashed_salt,
+            derivation,
+            create2_prefix,
+            bytecode_hash,
+            artifact,
+        })
+    }
+}
+
+impl RunHeader {
+    /// The settings that the run derived the addresses of its results with.
+    /// Runs that derived addresses differently from the given search can't be
+    /// checked against its init code hash, and neither can runs whose header
+    /// doesn't parse, so these describe why instead.
+    pub(crate) fn settings(&self, config: &Config) -> Result<RunSettings, String> {
+        let current = RunHeader::new(config);
+        if self.derivation != current.derivation {
+            let derivation = self.derivation.as_deref().unwrap_or("create2");
+            return Err(format!("run derived addresses differently ({derivation})"));
+        }
+
+        let unreadable = |err| format!("run header is unreadable ({err})");
+        let factory_address =
+            parse::address(Argument::Factory, &self.factory).map_err(unreadable)?;
+        let hashed_salt = self
+            .hashed_salt
+            .as_deref()
+            .map(SaltHash::parse)
+            .transpose()
+            .map_err(unreadable)?;
+        let create2_prefix = match &self.create2_prefix {
+            Some(create2_prefix) => {
+                let [create2_prefix] =
+                    parse::fixed(Argument::Create2Prefix, create2_prefix).map_err(unreadable)?;
+                create2_prefix
+            }
+            None => CONTROL_CHARACTER,
+        };
+
+        Ok(RunSettings {
+            factory_address,
+            hashed_salt,
+            derivation: config.derivation,
+            create2_prefix,
+        })
+    }
+}
+
+impl fmt::Display for RunHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "# factory={} caller={} init_code_hash={}",
+            self.factory, self.caller, self.init_code_hash
+        )?;
+        if let Some(hashed_salt) = &self.hashed_salt {
+            write!(f, " hashed_salt={hashed_salt}")?;
+        }
+        if let Some(derivation) = &self.derivation {
+            write!(f, " derivation={derivation}")?;
+        }
+        if let Some(create2_prefix) = &self.create2_prefix {
+            write!(f, " create2_prefix={create2_prefix}")?;
+        }
+        if let Some(bytecode_hash) = &self.bytecode_hash {
+            write!(f, " bytecode_hash={bytecode_hash}")?;
+        }
+        if let Some(artifact) = &self.artifact {
+            write!(f, " artifact={artifact}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything but the init code hash that the address deployed with a salt
+/// depends on, as recorded by the run header of the results of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RunSettings {
+    pub(crate) factory_address: [u8; 20],
+    pub(crate) hashed_salt: Option<SaltHash>,
+    pub(crate) derivation: Derivation,
+    pub(crate) create2_prefix: u8,
+}
+
+impl RunSettings {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            factory_address: config.factory_address,
+            hashed_salt: config.hashed_salt.clone(),
+            derivation: config.derivation,
+            create2_prefix: config.create2_prefix,
+        }
+    }
+
+    /// The address that the salt deploys the contract with the given init
+    /// code hash (or CREATE3 proxy init code hash, or versioned bytecode hash
+    /// on zkSync Era) to.
+    pub(crate) fn derive_address(&self, init_code_hash: &[u8; 32], salt: &[u8; 32]) -> Address {
+        let mut hash = Keccak::v256();
+        hash.update(
+            &self
+                .derivation
+                .preimage_header(self.create2_prefix, &self.factory_address),
+        );
+        match &self.hashed_salt {
+            Some(hashed_salt) => hash.update(&hashed_salt.hash(salt)),
+            None => hash.update(salt),
+        }
+        hash.update(init_code_hash);
+        hash.update(self.derivation.preimage_footer());
+
+        let mut res: [u8; 32] = [0; 32];
+        hash.finalize(&mut res);
+
+        self.derivation.address(&res[12..])
+    }
+}
+
+/// A salt found during a search and the address it was recorded to derive, as
+/// written to the output file: `0x<salt> => <address> => <reward>`, followed
+/// by e.g. ` | nonce 1 => <address> => <reward>` for each child searched along
+/// with it (which derive from the address, so need no checking of their own)
+/// or ` | deployment 1 => <address> => <reward>` for each other deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResultLine {
+    pub(crate) salt: [u8; 32],
+    pub(crate) address: Address,
+}
+
+impl ResultLine {
+    /// Parse a result line, returning `None` for any other line.
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split(" => ");
+        let salt = hex::decode(fields.next()?.trim()).ok()?.try_into().ok()?;
+        let address = fields.next()?.trim().parse().ok()?;
+
+        Some(Self { salt, address })
+    }
+}
+
+/// Read every run header from an output file, oldest first. A missing or
+/// unreadable file has no run headers.
+pub(crate) fn read_run_headers(path: &Path) -> Vec<RunHeader> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(RunHeader::parse)
+        .collect()
+}
+
+/// Warn if the build artifact that the init code was read from has changed
+/// since the last run that used it wrote to the output file, as the salts
+/// found by that run will not be valid for the new bytecode.
+pub(crate) fn warn_on_artifact_change(config: &Config) {
+    let current = RunHeader::new(config);
+    let Some(artifact) = &current.artifact else {
+        return;
+    };
+
+    let previous = read_run_headers(&config.output)
+        .into_iter()
+        .rev()
+        .find(|header| header.artifact.as_ref() == Some(artifact));
+
+    if let Some(previous) = previous {
+        if previous.bytecode_hash != current.bytecode_hash {
+            eprintln!(
+                "WARNING: the bytecode of {artifact} has changed since the last run that \
+                 used it (bytecode hash {} is now {}); salts found by earlier runs in `{}` do not \
+                 apply to the new bytecode.",
+                previous.bytecode_hash.as_deref().unwrap_or("unknown"),
+                current.bytecode_hash.as_deref().unwrap_or("unknown"),
+                config.output.display(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{search_config, CALLER, FACTORY, INIT_CODE_HASH};
+    use std::path::PathBuf;
+
+    const OTHER_FACTORY: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+    const OTHER_CALLER: &str = "0x3333333333333333333333333333333333333333";
+    const SALT: [u8; 32] = [0x5a; 32];
+
+    fn header(factory: &str, caller: &str) -> RunHeader {
+        RunHeader {
+            factory: factory.to_string(),
+            caller: caller.to_string(),
+            init_code_hash: INIT_CODE_HASH.to_string(),
+            hashed_salt: None,
+            derivation: None,
+            create2_prefix: None,
+            bytecode_hash: None,
+            artifact: None,
+        }
+    }
+
+    fn output(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("create2crunch-{name}-{}.txt", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn run_header_round_trips() {
+        let header = RunHeader {
+            hashed_salt: Some("fixed:0x0102,salt".to_string()),
+            derivation: Some("create3".to_string()),
+            create2_prefix: Some("0x41".to_string()),
+            bytecode_hash: Some(format!("0x{}", "ab".repeat(32))),
+            artifact: Some("out/My Token.sol/Token.json".to_string()),
+            ..header(FACTORY, CALLER)
+        };
+        assert_eq!(RunHeader::parse(&header.to_string()), Some(header));
+        assert_eq!(RunHeader::parse("0x01 => 0x02 => 3"), None);
+    }
+
+    #[test]
+    fn settings_come_from_the_run_header() {
+        let config = search_config(&["--caller", CALLER]);
+        let header = RunHeader {
+            hashed_salt: Some("fixed:0x0102,salt".to_string()),
+            create2_prefix: Some("0x41".to_string()),
+            ..header(OTHER_FACTORY, OTHER_CALLER)
+        };
+        let settings = header.settings(&config).unwrap();
+        assert_eq!(
+            settings,
+            RunSettings {
+                factory_address: parse::address(Argument::Factory, OTHER_FACTORY).unwrap(),
+                hashed_salt: Some(SaltHash::new(vec![1, 2], vec![])),
+                derivation: Derivation::Create2,
+                create2_prefix: 0x41,
+            }
+        );
+
+        // keccak256(0x41 ++ factory ++ keccak256(0x0102 ++ salt) ++ init code hash)
+        let mut preimage = vec![0x41];
+        preimage.extend_from_slice(&settings.factory_address);
+        preimage.extend_from_slice(&crate::keccak256(&[&[1, 2][..], &SALT].concat()));
+        preimage.extend_from_slice(&config.init_code_hash);
+        let expected = Address::from_slice(&crate::keccak256(&preimage)[12..]);
+        assert_eq!(
+            settings.derive_address(&config.init_code_hash, &SALT),
+            expected
+        );
+    }
+
+    #[test]
+    fn runs_that_derived_addresses_differently_are_skipped() {
+        let config = search_config(&["--caller", CALLER]);
+        let create3 = RunHeader {
+            derivation: Some("create3".to_string()),
+            ..header(FACTORY, CALLER)
+        };
+        assert!(create3.settings(&config).is_err());
+        assert!(header("0x1234", CALLER).settings(&config).is_err());
+        assert!(header(FACTORY, CALLER).settings(&config).is_ok());
+    }
+
+    #[test]
+    fn check_uses_the_factory_of_each_run() {
+        let config = search_config(&["--caller", CALLER]);
+        let init_code_hash = config.init_code_hash;
+
+        // a run with another factory and caller, whose salt still derives its
+        // address with the same init code
+        let other_factory: Address = OTHER_FACTORY.parse().unwrap();
+        let address = other_factory.create2(SALT, init_code_hash);
+        let contents = format!(
+            "{}\n0x{} => {address} => 3\n",
+            header(OTHER_FACTORY, OTHER_CALLER),
+            hex::encode(SALT)
+        );
+        let path = output("check-valid", &contents);
+        let args = ["--caller", CALLER, "--output", path.to_str().unwrap()];
+        assert!(crate::check(search_config(&args)).is_ok());
+
+        // a run of CREATE3 addresses, which can't be checked, and a result
+        // recorded for other init code, which is stale
+        let other_address = other_factory.create2(SALT, [0x33; 32]);
+        let create3 = RunHeader {
+            derivation: Some("create3".to_string()),
+            ..header(FACTORY, CALLER)
+        };
+        let contents = format!(
+            "{contents}{create3}\n0x{} => {other_address} => 3\n",
+            hex::encode(SALT)
+        );
+        fs::write(&path, &contents).unwrap();
+        assert!(crate::check(search_config(&args)).is_ok());
+
+        let contents = format!(
+            "{contents}{}\n0x{} => {other_address} => 3\n",
+            header(OTHER_FACTORY, OTHER_CALLER),
+            hex::encode(SALT)
+        );
+        fs::write(&path, &contents).unwrap();
+        assert!(crate::check(search_config(&args)).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -216,3 +216,9 @@ impl Reward {
         self.reward.get(value).copied()
     }
 }
+
+impl Default for Reward {
+    fn default() -> Self {
+        Self::new()
+    }
+}
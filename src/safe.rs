@@ -0,0 +1,258 @@
+use crate::cli::SafeArgs;
+use crate::error::{Argument, ConfigError};
+use crate::{abi, init_code, keccak256, parse};
+use alloy_primitives::hex;
+use serde::Deserialize;
+
+/// Signature of `Safe.setup`, called with the initializer built from owners.
+const SETUP_SIGNATURE: &str =
+    "setup(address[],uint256,address,bytes,address,address,uint256,address)";
+
+/// A release of the Safe contracts, determining the canonical addresses of
+/// its proxy factory, singletons and fallback handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+pub enum SafeVersion {
+    #[value(name = "1.3.0")]
+    #[serde(rename = "1.3.0")]
+    V1_3_0,
+    #[value(name = "1.4.1")]
+    #[serde(rename = "1.4.1")]
+    V1_4_1,
+}
+
+impl SafeVersion {
+    /// The canonical `SafeProxyFactory` deployment.
+    fn proxy_factory(self) -> &'static str {
+        match self {
+            SafeVersion::V1_3_0 => "0xa6b71e26c5e0845f74c812102ca7114b6a896ab2",
+            SafeVersion::V1_4_1 => "0x4e1dcf7ad4e460cfd30791ccc4f9c8a4f820ec67",
+        }
+    }
+
+    /// The canonical `Safe` (or, for L2s, `SafeL2`) singleton deployment.
+    fn singleton(self, l2: bool) -> &'static str {
+        match (self, l2) {
+            (SafeVersion::V1_3_0, false) => "0xd9db270c1b5e3bd161e8c8503c55ceabee709552",
+            (SafeVersion::V1_3_0, true) => "0x3e5c63644e683549055b9be8653de26e0b4cd36e",
+            (SafeVersion::V1_4_1, false) => "0x41675c099f32341bf84bfc5382af534df5c7461a",
+            (SafeVersion::V1_4_1, true) => "0x29fcb43b46531bca003ddc8fcb67ffe91900c762",
+        }
+    }
+
+    /// The canonical `CompatibilityFallbackHandler` deployment.
+    fn fallback_handler(self) -> &'static str {
+        match self {
+            SafeVersion::V1_3_0 => "0xf48f2b2d2a534e402487b3ee7c18c33aec0fe5e4",
+            SafeVersion::V1_4_1 => "0xfd0732dc9e303f09fcef3a7388ad10a83459ec99",
+        }
+    }
+}
+
+/// The parameters of `createProxyWithNonce` that the address of a Safe is
+/// derived from, other than the `saltNonce`: the proxy factory deploys
+/// `proxyCreationCode ++ uint256(singleton)` via CREATE2, with a salt of
+/// `keccak256(keccak256(initializer) ++ saltNonce)`.
+pub(crate) struct SafePreset {
+    pub(crate) factory_address: [u8; 20],
+    pub(crate) init_code: Vec<u8>,
+    pub(crate) initializer_hash: [u8; 32],
+}
+
+impl SafePreset {
+    pub(crate) fn new(
+        args: &SafeArgs,
+        version: SafeVersion,
+        factory: Option<&str>,
+    ) -> Result<Self, ConfigError> {
+        let factory_address = parse::address(
+            Argument::Factory,
+            factory.unwrap_or(version.proxy_factory()),
+        )?;
+
+        // the proxy creation code differs between versions (and compilations),
+        // so it is read from the factory rather than assumed
+        let Some(proxy_code) = &args.proxy_code else {
+            return Err(ConfigError::Requires(
+                Argument::Safe,
+                Argument::SafeProxyCode,
+            ));
+        };
+        let mut init_code = init_code::read(Argument::SafeProxyCode, proxy_code)?;
+
+        let singleton = match &args.singleton {
+            Some(singleton) => parse::address(Argument::SafeSingleton, singleton)?,
+            None => parse::address(Argument::SafeSingleton, version.singleton(args.l2))?,
+        };
+        init_code.extend_from_slice(&[0; 12]);
+        init_code.extend_from_slice(&singleton);
+
+        let initializer = match (&args.initializer, args.owners.is_empty()) {
+            (Some(initializer), _) => parse::hex(Argument::SafeInitializer, initializer)?,
+            (None, false) => setup_call(args, version)?,
+            (None, true) => {
+                return Err(ConfigError::Requires(Argument::Safe, Argument::SafeOwners))
+            }
+        };
+
+        Ok(Self {
+            factory_address,
+            init_code,
+            initializer_hash: keccak256(&initializer),
+        })
+    }
+}
+
+/// Build the call data of `setup` for a Safe with the given owners and
+/// threshold, the fallback handler and no module setup or payment.
+fn setup_call(args: &SafeArgs, version: SafeVersion) -> Result<Vec<u8>, ConfigError> {
+    let owners = args
+        .owners
+        .iter()
+        .map(|owner| parse::address(Argument::SafeOwners, owner).map(hex::encode))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let threshold = args.threshold.unwrap_or(1);
+    if threshold == 0 || threshold > owners.len() as u64 {
+        return Err(ConfigError::InvalidSafeThreshold {
+            threshold,
+            owners: owners.len(),
+        });
+    }
+
+    let fallback_handler = parse::address(
+        Argument::SafeFallbackHandler,
+        args.fallback_handler
+            .as_deref()
+            .unwrap_or(version.fallback_handler()),
+    )?;
+
+    // to, data, paymentToken, payment and paymentReceiver are left empty
+    let zero = hex::encode([0; 20]);
+    let values = [
+        format!("[{}]", owners.join(",")),
+        threshold.to_string(),
+        zero.clone(),
+        String::new(),
+        hex::encode(fallback_handler),
+        zero.clone(),
+        "0".to_string(),
+        zero,
+    ];
+
+    let mut call = keccak256(SETUP_SIGNATURE.as_bytes())[..4].to_vec();
+    call.extend(abi::encode_constructor_args(SETUP_SIGNATURE, &values)?);
+    Ok(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::config;
+    use alloy_primitives::Address;
+
+    const OWNERS: [&str; 2] = [
+        "0x1111111111111111111111111111111111111111",
+        "0x2222222222222222222222222222222222222222",
+    ];
+
+    /// Stands in for the proxy creation code returned by the factory.
+    const PROXY_CODE: &str = "0x600a600c600039600a6000f3602a60005260206000f3";
+
+    fn safe_args() -> SafeArgs {
+        SafeArgs {
+            proxy_code: Some(PROXY_CODE.to_string()),
+            owners: OWNERS.map(String::from).to_vec(),
+            threshold: Some(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builds_the_setup_call() {
+        // `setup` with the owners, a threshold of 2 and the 1.4.1 fallback
+        // handler, as encoded by an independent ABI encoder
+        let expected = format!(
+            "b63e800d\
+             {:064x}{:064x}{:064x}{:064x}\
+             000000000000000000000000fd0732dc9e303f09fcef3a7388ad10a83459ec99\
+             {:064x}{:064x}{:064x}\
+             {:064x}\
+             0000000000000000000000001111111111111111111111111111111111111111\
+             0000000000000000000000002222222222222222222222222222222222222222\
+             {:064x}",
+            0x100, 2, 0, 0x160, 0, 0, 0, 2, 0
+        );
+        let call = setup_call(&safe_args(), SafeVersion::V1_4_1).unwrap();
+        assert_eq!(hex::encode(call), expected);
+    }
+
+    #[test]
+    fn derives_create_proxy_with_nonce_addresses() {
+        // the 1.4.1 factory deploys the proxy creation code followed by the
+        // 1.4.1 singleton, with a salt of keccak256(keccak256(initializer) ++
+        // saltNonce), computed independently for a saltNonce of 0x1234
+        let mut args = vec!["--safe", "1.4.1", "--safe-proxy-code", PROXY_CODE];
+        args.extend(["--safe-threshold", "2", "--safe-owners"]);
+        args.extend(OWNERS);
+        let search = config(&args).unwrap();
+
+        let mut salt_nonce = [0; 32];
+        salt_nonce[30..].copy_from_slice(&[0x12, 0x34]);
+        assert_eq!(
+            search.hashed_salt.as_ref().unwrap().hash(&salt_nonce),
+            hex!("c1250ca13f7184900b5e006152446b830600697c243b417b35c8932ab152ee92")
+        );
+        assert_eq!(
+            crate::derive_address(&search, &salt_nonce),
+            "0x9B3d278236b795e0A2840AD3EF6AEcAaFaF0280f"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn appends_the_singleton_to_the_proxy_code() {
+        let preset = SafePreset::new(&safe_args(), SafeVersion::V1_3_0, None).unwrap();
+        assert_eq!(
+            preset.factory_address,
+            hex!("a6b71e26c5e0845f74c812102ca7114b6a896ab2")
+        );
+        let singleton = hex!("d9db270c1b5e3bd161e8c8503c55ceabee709552");
+        let proxy_code = hex::decode(PROXY_CODE).unwrap();
+        assert_eq!(
+            preset.init_code,
+            [&proxy_code[..], &[0; 12], &singleton].concat()
+        );
+
+        let l2 = SafeArgs {
+            l2: true,
+            ..safe_args()
+        };
+        let preset = SafePreset::new(&l2, SafeVersion::V1_3_0, None).unwrap();
+        assert_eq!(
+            preset.init_code[proxy_code.len() + 12..],
+            hex!("3e5c63644e683549055b9be8653de26e0b4cd36e")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_safes() {
+        let no_proxy_code = SafeArgs {
+            proxy_code: None,
+            ..safe_args()
+        };
+        assert!(SafePreset::new(&no_proxy_code, SafeVersion::V1_4_1, None).is_err());
+
+        let too_high = SafeArgs {
+            threshold: Some(3),
+            ..safe_args()
+        };
+        assert!(matches!(
+            SafePreset::new(&too_high, SafeVersion::V1_4_1, None),
+            Err(ConfigError::InvalidSafeThreshold {
+                threshold: 3,
+                owners: 2
+            })
+        ));
+    }
+}
@@ -0,0 +1,483 @@
+use crate::error::{Argument, ConfigError};
+use crate::{keccak256, parse};
+use serde::Deserialize;
+use std::fmt;
+use std::ops::Range;
+
+/// The layout template used when none is given: the calling address, then a
+/// random 6-byte segment (to prevent collisions with other runs), then a
+/// 6-byte counter (stepped through during the run).
+pub const DEFAULT_SALT_LAYOUT: &str = "caller,random:6,counter:6";
+
+/// The layout template used on the GPU when none is given, where the kernel
+/// fills an 8-byte counter with the work item id and a 4-byte nonce, leaving a
+/// random 4-byte segment.
+pub const DEFAULT_GPU_SALT_LAYOUT: &str = "caller,random:4,counter:8";
+
+/// How the first 20 bytes of the salt are filled when they are not bound to
+/// the caller, for factories that do not check them (or require them to be
+/// zero). Such salts offer no protection against frontrunning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnboundSalt {
+    /// The first 20 bytes are zero.
+    Zero,
+    /// The first 20 bytes are random, like the rest of the salt.
+    Random,
+}
+
+impl UnboundSalt {
+    /// The layout template used for salts in this mode, on the GPU or not.
+    pub fn salt_layout(self, gpu: bool) -> &'static str {
+        match (self, gpu) {
+            (UnboundSalt::Zero, false) => "zero:20,random:6,counter:6",
+            (UnboundSalt::Zero, true) => "zero:20,random:4,counter:8",
+            (UnboundSalt::Random, false) => "random:26,counter:6",
+            (UnboundSalt::Random, true) => "random:24,counter:8",
+        }
+    }
+}
+
+/// A region of the 32-byte salt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaltRegion {
+    /// Bytes that are the same for every salt, such as the calling address.
+    Fixed(Vec<u8>),
+    /// Bytes that are chosen at random for each batch of salts.
+    Random(usize),
+    /// Bytes holding a little-endian counter that is stepped through within
+    /// each batch of salts.
+    Counter(usize),
+}
+
+/// Describes how each 32-byte salt is built from fixed, random and counter
+/// regions, shared by the CPU and GPU searches. Written as a comma-separated
+/// template of regions, e.g. `caller,random:6,counter:6`:
+///   - `caller`: the 20-byte calling address
+///   - `zero:N`: N zero bytes
+///   - `fixed:HEX`: the given hex-encoded bytes
+///   - `random:N`: N random bytes
+///   - `counter:N`: an N-byte counter (exactly one is required, of 1 to 8 bytes)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaltLayout {
+    regions: Vec<SaltRegion>,
+}
+
+impl SaltLayout {
+    /// Parse a layout template, resolving `caller` to the given address.
+    pub fn parse(template: &str, calling_address: &[u8; 20]) -> Result<Self, ConfigError> {
+        let invalid = ConfigError::InvalidSaltLayout;
+
+        let mut regions = vec![];
+        for region in template.split(',') {
+            let region = region.trim();
+            let (kind, value) = region.split_once(':').unwrap_or((region, ""));
+            let size = || {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| invalid("expected a size like `random:4`"))
+            };
+
+            regions.push(match kind {
+                "caller" if value.is_empty() => SaltRegion::Fixed(calling_address.to_vec()),
+                "zero" => SaltRegion::Fixed(vec![0; size()?]),
+                "fixed" => SaltRegion::Fixed(parse::hex(Argument::SaltLayout, value)?),
+                "random" => SaltRegion::Random(size()?),
+                "counter" => SaltRegion::Counter(size()?),
+                _ => {
+                    return Err(invalid(
+                        "unknown region (expected caller, zero, fixed, random or counter)",
+                    ))
+                }
+            });
+        }
+
+        Self::new(regions)
+    }
+
+    /// Construct a layout from its regions, which must total 32 bytes and
+    /// include exactly one counter region of 1 to 8 bytes.
+    pub fn new(regions: Vec<SaltRegion>) -> Result<Self, ConfigError> {
+        let invalid = ConfigError::InvalidSaltLayout;
+
+        let len: usize = regions.iter().map(SaltRegion::len).sum();
+        if len != 32 {
+            return Err(invalid("the regions must total 32 bytes"));
+        }
+
+        let counters: Vec<usize> = regions
+            .iter()
+            .filter_map(|region| match region {
+                SaltRegion::Counter(len) => Some(*len),
+                _ => None,
+            })
+            .collect();
+        match counters[..] {
+            [len] if (1..=8).contains(&len) => {}
+            [_] => return Err(invalid("the counter region must be 1 to 8 bytes")),
+            _ => return Err(invalid("exactly one counter region is required")),
+        }
+
+        Ok(Self { regions })
+    }
+
+    pub fn regions(&self) -> &[SaltRegion] {
+        &self.regions
+    }
+
+    /// Whether every salt starts with the given calling address, which a
+    /// factory with frontrunning protection checks against its caller.
+    pub fn is_bound_to(&self, calling_address: &[u8; 20]) -> bool {
+        match self.regions.first() {
+            Some(SaltRegion::Fixed(fixed)) => fixed.starts_with(calling_address),
+            _ => false,
+        }
+    }
+
+    /// The salt beginning with another caller in place of the given calling
+    /// address, if the salt is bound to it, and otherwise the salt as is.
+    pub fn rebind(
+        &self,
+        salt: &[u8; 32],
+        calling_address: &[u8; 20],
+        other_calling_address: &[u8; 20],
+    ) -> [u8; 32] {
+        let mut salt = *salt;
+        if self.is_bound_to(calling_address) {
+            salt[..20].copy_from_slice(other_calling_address);
+        }
+        salt
+    }
+
+    /// The layout of salts beginning with another caller in place of the given
+    /// calling address, if the salt is bound to it, and otherwise the layout as
+    /// is.
+    pub fn rebound(&self, calling_address: &[u8; 20], other_calling_address: &[u8; 20]) -> Self {
+        let mut layout = self.clone();
+        if self.is_bound_to(calling_address) {
+            if let Some(SaltRegion::Fixed(fixed)) = layout.regions.first_mut() {
+                fixed[..20].copy_from_slice(other_calling_address);
+            }
+        }
+        layout
+    }
+
+    /// The bytes that every salt begins with, up to the first random or
+    /// counter region.
+    pub fn fixed_prefix(&self) -> Vec<u8> {
+        self.regions
+            .iter()
+            .map_while(|region| match region {
+                SaltRegion::Fixed(fixed) => Some(fixed.as_slice()),
+                _ => None,
+            })
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// The total number of random bytes in the layout.
+    pub fn random_len(&self) -> usize {
+        self.regions
+            .iter()
+            .map(|region| match region {
+                SaltRegion::Random(len) => *len,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// The byte offsets of the counter region within the salt.
+    pub fn counter_range(&self) -> Range<usize> {
+        let mut offset = 0;
+        for region in &self.regions {
+            if let SaltRegion::Counter(len) = region {
+                return offset..offset + len;
+            }
+            offset += region.len();
+        }
+        unreachable!("layouts always have a counter region")
+    }
+
+    /// The largest value that the counter region can hold.
+    pub fn max_counter(&self) -> u64 {
+        u64::MAX >> (64 - 8 * self.counter_range().len())
+    }
+
+    /// Build the salt holding the given random bytes (`random_len` of them,
+    /// filling each random region in turn) and counter value.
+    pub fn salt(&self, random: &[u8], counter: u64) -> [u8; 32] {
+        let mut salt = [0; 32];
+        let mut random = random.iter().copied();
+
+        let mut offset = 0;
+        for region in &self.regions {
+            let bytes = &mut salt[offset..offset + region.len()];
+            match region {
+                SaltRegion::Fixed(fixed) => bytes.copy_from_slice(fixed),
+                SaltRegion::Random(_) => bytes.fill_with(|| random.next().unwrap_or_default()),
+                SaltRegion::Counter(len) => bytes.copy_from_slice(&counter.to_le_bytes()[..*len]),
+            }
+            offset += region.len();
+        }
+
+        salt
+    }
+}
+
+impl SaltRegion {
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        match self {
+            SaltRegion::Fixed(fixed) => fixed.len(),
+            SaltRegion::Random(len) | SaltRegion::Counter(len) => *len,
+        }
+    }
+}
+
+impl fmt::Display for SaltLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, region) in self.regions.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match region {
+                SaltRegion::Fixed(fixed) => {
+                    write!(f, "fixed:0x{}", alloy_primitives::hex::encode(fixed))?
+                }
+                SaltRegion::Random(len) => write!(f, "random:{len}")?,
+                SaltRegion::Counter(len) => write!(f, "counter:{len}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Describes how factories that hash a user-supplied salt into the real
+/// CREATE2 salt compute it, as `keccak256(prefix ++ salt ++ suffix)`. Written
+/// as a comma-separated template of parts, e.g. `keccak:0x...,salt` for a Safe
+/// proxy factory (with the `setup` call data as the initializer):
+///   - `salt`: the 32-byte salt passed to the factory (exactly one is required)
+///   - `fixed:HEX`: the given hex-encoded bytes
+///   - `keccak:HEX`: the keccak-256 hash of the given hex-encoded bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaltHash {
+    prefix: Vec<u8>,
+    suffix: Vec<u8>,
+}
+
+impl SaltHash {
+    /// Construct a hash of the salt from the bytes hashed around it.
+    pub fn new(prefix: Vec<u8>, suffix: Vec<u8>) -> Self {
+        Self { prefix, suffix }
+    }
+
+    /// Parse a preimage template for the hash of the salt.
+    pub fn parse(template: &str) -> Result<Self, ConfigError> {
+        let invalid = ConfigError::InvalidHashedSalt;
+
+        let mut prefix = vec![];
+        let mut suffix = None;
+        for part in template.split(',') {
+            let part = part.trim();
+            let (kind, value) = part.split_once(':').unwrap_or((part, ""));
+
+            let bytes = match kind {
+                "salt" if value.is_empty() => {
+                    if suffix.is_some() {
+                        return Err(invalid("exactly one salt part is required"));
+                    }
+                    suffix = Some(vec![]);
+                    continue;
+                }
+                "fixed" => parse::hex(Argument::HashedSalt, value)?,
+                "keccak" => keccak256(&parse::hex(Argument::HashedSalt, value)?).to_vec(),
+                _ => return Err(invalid("unknown part (expected salt, fixed or keccak)")),
+            };
+            match &mut suffix {
+                Some(suffix) => suffix.extend(bytes),
+                None => prefix.extend(bytes),
+            }
+        }
+
+        let Some(suffix) = suffix else {
+            return Err(invalid("exactly one salt part is required"));
+        };
+        Ok(Self { prefix, suffix })
+    }
+
+    /// The bytes hashed ahead of the salt.
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    /// The bytes hashed after the salt.
+    pub fn suffix(&self) -> &[u8] {
+        &self.suffix
+    }
+
+    /// Compute the CREATE2 salt that the factory derives from the given salt.
+    pub fn hash(&self, salt: &[u8; 32]) -> [u8; 32] {
+        keccak256(&[&self.prefix[..], salt, &self.suffix[..]].concat())
+    }
+}
+
+impl fmt::Display for SaltHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.prefix.is_empty() {
+            write!(
+                f,
+                "fixed:0x{},",
+                alloy_primitives::hex::encode(&self.prefix)
+            )?;
+        }
+        f.write_str("salt")?;
+        if !self.suffix.is_empty() {
+            write!(
+                f,
+                ",fixed:0x{}",
+                alloy_primitives::hex::encode(&self.suffix)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALLER: [u8; 20] = [0x11; 20];
+    const OTHER: [u8; 20] = [0x22; 20];
+
+    #[test]
+    fn parses_each_region() {
+        let layout =
+            SaltLayout::parse("caller, zero:2,fixed:0xabcd,random:2,counter:6", &CALLER).unwrap();
+        assert_eq!(
+            layout.regions(),
+            [
+                SaltRegion::Fixed(CALLER.to_vec()),
+                SaltRegion::Fixed(vec![0, 0]),
+                SaltRegion::Fixed(vec![0xab, 0xcd]),
+                SaltRegion::Random(2),
+                SaltRegion::Counter(6),
+            ]
+        );
+        assert_eq!(layout.random_len(), 2);
+        assert_eq!(layout.counter_range(), 26..32);
+        assert_eq!(layout.max_counter(), 0xffffffffffff);
+        assert_eq!(
+            layout.fixed_prefix(),
+            [&CALLER[..], &[0, 0, 0xab, 0xcd]].concat()
+        );
+    }
+
+    #[test]
+    fn built_in_layouts_are_valid() {
+        let mut templates = vec![DEFAULT_SALT_LAYOUT, DEFAULT_GPU_SALT_LAYOUT];
+        for unbound_salt in [UnboundSalt::Zero, UnboundSalt::Random] {
+            templates.push(unbound_salt.salt_layout(false));
+            templates.push(unbound_salt.salt_layout(true));
+        }
+        for template in templates {
+            // and are shown as templates that parse back to them
+            let layout = SaltLayout::parse(template, &CALLER).unwrap();
+            assert_eq!(
+                SaltLayout::parse(&layout.to_string(), &OTHER).unwrap(),
+                layout
+            );
+        }
+
+        // the CPU steps through a 6-byte counter and the GPU an 8-byte one
+        let cpu = SaltLayout::parse(DEFAULT_SALT_LAYOUT, &CALLER).unwrap();
+        assert_eq!((cpu.random_len(), cpu.counter_range()), (6, 26..32));
+        let gpu = SaltLayout::parse(DEFAULT_GPU_SALT_LAYOUT, &CALLER).unwrap();
+        assert_eq!((gpu.random_len(), gpu.counter_range()), (4, 24..32));
+    }
+
+    #[test]
+    fn rejects_invalid_layouts() {
+        for template in [
+            "caller,random:6,counter:5",
+            "caller,random:6,counter:7",
+            "caller,random:12",
+            "caller,counter:6,counter:6",
+            "caller,random:3,counter:9",
+            "zero:32,counter:0",
+            "caller,random:x,counter:6",
+            "caller:0x11,random:6,counter:6",
+            "sender,random:6,counter:6",
+            "fixed:0xzz,random:11,counter:20",
+        ] {
+            assert!(
+                SaltLayout::parse(template, &CALLER).is_err(),
+                "{template} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn fills_each_region_of_the_salt() {
+        let layout =
+            SaltLayout::parse("caller,random:2,fixed:0xee,random:3,counter:6", &CALLER).unwrap();
+        let salt = layout.salt(&[1, 2, 3, 4, 5], 0x0a0b0c0d0e0f);
+
+        let mut expected = [0; 32];
+        expected[..20].copy_from_slice(&CALLER);
+        expected[20..26].copy_from_slice(&[1, 2, 0xee, 3, 4, 5]);
+        // the counter is little-endian
+        expected[26..].copy_from_slice(&[0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a]);
+        assert_eq!(salt, expected);
+    }
+
+    #[test]
+    fn counter_may_sit_anywhere() {
+        let layout = SaltLayout::parse("counter:2,random:10,caller", &CALLER).unwrap();
+        assert_eq!(layout.counter_range(), 0..2);
+        assert_eq!(layout.max_counter(), 0xffff);
+        assert!(!layout.is_bound_to(&CALLER));
+        assert!(layout.fixed_prefix().is_empty());
+
+        let salt = layout.salt(&[7; 10], 0x0102);
+        assert_eq!(salt[..2], [0x02, 0x01]);
+        assert_eq!(salt[2..12], [7; 10]);
+        assert_eq!(salt[12..], CALLER);
+    }
+
+    #[test]
+    fn rebinds_salts_bound_to_the_caller() {
+        let layout = SaltLayout::parse(DEFAULT_SALT_LAYOUT, &CALLER).unwrap();
+        assert!(layout.is_bound_to(&CALLER));
+        assert!(!layout.is_bound_to(&OTHER));
+
+        let salt = layout.salt(&[9; 6], 42);
+        let rebound = layout.rebind(&salt, &CALLER, &OTHER);
+        assert_eq!(rebound[..20], OTHER);
+        assert_eq!(rebound[20..], salt[20..]);
+
+        // the rebound layout builds the rebound salts
+        let other_layout = layout.rebound(&CALLER, &OTHER);
+        assert!(other_layout.is_bound_to(&OTHER));
+        assert_eq!(other_layout.salt(&[9; 6], 42), rebound);
+        assert_eq!(
+            other_layout,
+            SaltLayout::parse(DEFAULT_SALT_LAYOUT, &OTHER).unwrap()
+        );
+    }
+
+    #[test]
+    fn leaves_unbound_salts_alone() {
+        // a zero caller is only a prefix of zeroes, not bound to another caller
+        let layout = SaltLayout::parse(UnboundSalt::Zero.salt_layout(false), &CALLER).unwrap();
+        assert!(!layout.is_bound_to(&CALLER));
+
+        let salt = layout.salt(&[9; 6], 42);
+        assert_eq!(layout.rebind(&salt, &CALLER, &OTHER), salt);
+        assert_eq!(layout.rebound(&CALLER, &OTHER), layout);
+
+        let layout = SaltLayout::parse(UnboundSalt::Random.salt_layout(false), &CALLER).unwrap();
+        assert!(!layout.is_bound_to(&CALLER));
+        assert_eq!(layout.rebound(&CALLER, &OTHER), layout);
+    }
+}
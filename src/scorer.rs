@@ -0,0 +1,120 @@
+use crate::children::zero_bytes;
+use crate::reward::Reward;
+use crate::target::Quality;
+use alloy_primitives::Address;
+use std::borrow::Cow;
+
+/// How good an address is, as found by a `Scorer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    /// The value of the address, with higher values being better.
+    pub value: f64,
+    /// How the score is shown after the address, e.g. the approximate rarity
+    /// of its zero bytes.
+    pub label: Cow<'static, str>,
+}
+
+/// Decides which addresses are worth reporting, and how good each one is. The
+/// default scorer is `Reward`, which scores the rarity of the leading and
+/// total zero bytes of an address; other scorers may be set on the config of
+/// a search, of `deployer`, `plan` or `mutate`.
+///
+/// The GPU search only reports addresses that meet the zero byte thresholds,
+/// and shows those that the scorer doesn't score with a value of `0`.
+pub trait Scorer: Send + Sync {
+    /// Score an address, returning `None` if it isn't worth reporting.
+    fn score(&self, address: &Address) -> Option<Score>;
+
+    /// Whether an address reaches a quality, as required of every address by
+    /// the thresholds when children or other deployments are searched, and
+    /// of a target by its `until`. By default, an address reaches a quality
+    /// with at least its number of leading or of total zero bytes.
+    fn reaches(&self, address: &Address, quality: Quality) -> bool {
+        let (leading, total) = zero_bytes(address);
+        quality.is_reached(leading, total)
+    }
+}
+
+impl Scorer for Reward {
+    /// Score an address by the rarity of its leading and total zero bytes,
+    /// valued at the inverse of the probability of a random address having
+    /// them.
+    fn score(&self, address: &Address) -> Option<Score> {
+        // no address with fewer than three zero bytes has a reward, so most
+        // are turned away before the lookup
+        let (leading, total) = zero_bytes(address);
+        if total < 3 {
+            return None;
+        }
+        let reward = self.get(&(leading * 20 + total))?;
+
+        Some(Score {
+            value: 1.0 / probability(leading, total),
+            label: Cow::Borrowed(reward),
+        })
+    }
+}
+
+/// The probability of a random address having exactly the given number of
+/// leading and of total zero bytes.
+fn probability(leading: usize, total: usize) -> f64 {
+    let zero: f64 = 1.0 / 256.0;
+    let non_zero: f64 = 255.0 / 256.0;
+    if leading == 20 {
+        return zero.powi(20);
+    }
+
+    // the leading zero bytes, the non-zero byte after them, and the other zero
+    // bytes anywhere among the rest
+    let rest = 19 - leading;
+    let zeroes = total - leading;
+    let choices = (0..zeroes).fold(1.0, |choices, i| {
+        choices * (rest - i) as f64 / (i + 1) as f64
+    });
+
+    zero.powi(leading as i32)
+        * non_zero
+        * choices
+        * zero.powi(zeroes as i32)
+        * non_zero.powi((rest - zeroes) as i32)
+}
+
+/// The label of the score of an address, or `0` if it isn't scored.
+pub(crate) fn label(scorer: &dyn Scorer, address: &Address) -> Cow<'static, str> {
+    scorer
+        .score(address)
+        .map_or(Cow::Borrowed("0"), |score| score.label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probabilities_sum_to_one() {
+        let sum: f64 = (0..=20)
+            .flat_map(|leading| (leading..=20).map(move |total| (leading, total)))
+            .filter(|&(leading, total)| leading < 20 || total == 20)
+            .filter(|&(leading, total)| leading == 20 || total < 20)
+            .map(|(leading, total)| probability(leading, total))
+            .sum();
+        assert!((sum - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rarer_addresses_are_worth_more() {
+        let reward = Reward::new();
+        let value = |address: &str| reward.score(&address.parse().unwrap()).unwrap().value;
+
+        let three_leading = value("0x000000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        let four_leading = value("0x00000000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        assert!(four_leading > three_leading);
+        assert!(reward
+            .score(
+                &"0x0000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+                    .parse()
+                    .unwrap()
+            )
+            .is_none());
+    }
+}
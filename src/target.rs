@@ -0,0 +1,103 @@
+use crate::error::{Argument, ConfigError};
+use crate::{init_code, keccak256, parse};
+use std::path::PathBuf;
+
+/// The quality at which a target is done and drops out of the search: an
+/// address with at least this many leading or total zero bytes, written like
+/// `4/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quality {
+    pub leading_zeroes: u8,
+    pub total_zeroes: u8,
+}
+
+impl Quality {
+    /// Parse a quality like `4/8`, returning `None` if it is malformed.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (leading, total) = value.split_once('/')?;
+        let leading_zeroes = leading.trim().parse::<u8>().ok()?;
+        let total_zeroes = total.trim().parse::<u8>().ok()?;
+        if leading_zeroes > 20 || (total_zeroes > 20 && total_zeroes != 255) {
+            return None;
+        }
+
+        Some(Self {
+            leading_zeroes,
+            total_zeroes,
+        })
+    }
+
+    /// Whether an address with the given number of leading and total zero
+    /// bytes reaches the quality.
+    pub(crate) fn is_reached(self, leading: usize, total: usize) -> bool {
+        leading >= self.leading_zeroes as usize || total >= self.total_zeroes as usize
+    }
+}
+
+/// A contract searched for in the same pass as the contract of the search,
+/// deployed by the same factory with its own init code and possibly its own
+/// caller, whose salts are written to its own output file. Written as
+/// comma-separated `key=value` fields, e.g.
+/// `init_code_hash=0x...,caller=0x...,output=vault.txt,until=4/8`:
+///   - `init_code_hash`, or `init_code` (hex-encoded or as a file path)
+///   - `caller`: the caller that begins each salt [default: --caller]
+///   - `output`: the file that its salts are appended to
+///   - `until`: the quality at which it drops out of the search (see `Quality`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub calling_address: [u8; 20],
+    pub init_code_hash: [u8; 32],
+    pub output: PathBuf,
+    pub until: Option<Quality>,
+}
+
+impl Target {
+    /// Parse a target, taking the caller of the search when none is given.
+    pub(crate) fn parse(value: &str, calling_address: &[u8; 20]) -> Result<Self, ConfigError> {
+        let invalid = ConfigError::InvalidTarget;
+
+        let mut init_code_hash = None;
+        let mut caller = None;
+        let mut output = None;
+        let mut until = None;
+        for field in value.split(',') {
+            let Some((key, value)) = field.trim().split_once('=') else {
+                return Err(invalid("expected fields like `init_code_hash=0x...`"));
+            };
+
+            match key {
+                "init_code_hash" | "init_code" if init_code_hash.is_some() => {
+                    return Err(invalid(
+                        "only one of init_code_hash or init_code may be given",
+                    ))
+                }
+                "init_code_hash" => {
+                    init_code_hash = Some(parse::fixed(Argument::Target, value)?);
+                }
+                "init_code" => {
+                    let init_code = init_code::read(Argument::Target, value)?;
+                    init_code_hash = Some(keccak256(&init_code));
+                }
+                "caller" => caller = Some(parse::address(Argument::Target, value)?),
+                "output" => output = Some(PathBuf::from(value)),
+                "until" => {
+                    until = Some(
+                        Quality::parse(value)
+                            .ok_or_else(|| invalid("expected a quality like `until=4/8`"))?,
+                    );
+                }
+                _ => return Err(invalid(
+                    "unknown field (expected init_code_hash, init_code, caller, output or until)",
+                )),
+            }
+        }
+
+        Ok(Self {
+            calling_address: caller.unwrap_or(*calling_address),
+            init_code_hash: init_code_hash
+                .ok_or_else(|| invalid("an init_code_hash or init_code is required"))?,
+            output: output.ok_or_else(|| invalid("an output file is required"))?,
+            until,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use crate::error::{Argument, ConfigError};
+use sha2::{Digest, Sha256};
+
+/// The byte that the TVM begins the CREATE2 preimage with, in place of `0xff`.
+pub(crate) const CREATE2_PREFIX: u8 = 0x41;
+
+/// The byte that Tron addresses are prefixed with before they are encoded.
+const ADDRESS_PREFIX: u8 = 0x41;
+
+/// The Bitcoin base58 alphabet, which Tron addresses are encoded with.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encode an address the way Tron shows it (e.g. `T...`): base58check of the
+/// address prefixed with `0x41`, with a checksum of the first four bytes of
+/// its double SHA-256 hash.
+pub(crate) fn base58_address(address: &[u8; 20]) -> String {
+    let mut payload = vec![ADDRESS_PREFIX];
+    payload.extend_from_slice(address);
+    let checksum = Sha256::digest(Sha256::digest(&payload));
+    payload.extend_from_slice(&checksum[..4]);
+
+    // repeatedly divide the payload, as a big-endian number, by 58; the prefix
+    // is never zero, so there are no leading zero bytes to encode as `1`s
+    let mut digits = vec![];
+    let mut number = payload;
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 58) as u8;
+            remainder = value % 58;
+        }
+        digits.push(ALPHABET[remainder as usize]);
+    }
+
+    digits.iter().rev().map(|&digit| digit as char).collect()
+}
+
+/// Decode a Tron base58check address (e.g. `T...`), verifying its prefix and
+/// checksum.
+pub(crate) fn parse_base58_address(
+    argument: Argument,
+    value: &str,
+) -> Result<[u8; 20], ConfigError> {
+    let invalid = || ConfigError::InvalidBase58(argument);
+
+    // multiply the number decoded so far by 58 and add each digit, keeping it
+    // as big-endian bytes
+    let mut number = [0u8; 25];
+    for character in value.trim().bytes() {
+        let digit = ALPHABET
+            .iter()
+            .position(|&c| c == character)
+            .ok_or_else(invalid)?;
+
+        let mut carry = digit as u32;
+        for byte in number.iter_mut().rev() {
+            let value = *byte as u32 * 58 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(invalid());
+        }
+    }
+
+    let (payload, checksum) = number.split_at(21);
+    if payload[0] != ADDRESS_PREFIX || Sha256::digest(Sha256::digest(payload))[..4] != *checksum {
+        return Err(invalid());
+    }
+
+    Ok(payload[1..].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::RunSettings;
+    use crate::tests::search_config;
+    use crate::Derivation;
+    use alloy_primitives::{hex, Address};
+
+    /// USDT on Tron, and the hex address it encodes (without the `0x41`).
+    const USDT: &str = "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t";
+    const USDT_HEX: [u8; 20] = hex!("a614f803b6fd780986a42c78ec9c7f77e6ded13c");
+
+    #[test]
+    fn base58_round_trips() {
+        assert_eq!(base58_address(&USDT_HEX), USDT);
+        assert_eq!(
+            parse_base58_address(Argument::Factory, USDT).unwrap(),
+            USDT_HEX
+        );
+
+        // the address of all zeroes
+        let zero = "T9yD14Nj9j7xAB4dbGeiX9h8unkKHxuWwb";
+        assert_eq!(base58_address(&[0; 20]), zero);
+        assert_eq!(
+            parse_base58_address(Argument::Caller, zero).unwrap(),
+            [0; 20]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_base58() {
+        for value in [
+            // the last digit of the checksum changed
+            "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6u",
+            // a Bitcoin address, prefixed with 0x00 rather than 0x41
+            "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+            // `0` is not a base58 digit
+            "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj60",
+            // too long to be an address
+            "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6tTR7NH",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    parse_base58_address(Argument::Factory, value),
+                    Err(ConfigError::InvalidBase58(Argument::Factory))
+                ),
+                "{value} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn tron_addresses_are_accepted_for_tron() {
+        let config = search_config(&["--tron", "--caller", USDT]);
+        assert_eq!(config.calling_address, USDT_HEX);
+        assert_eq!(config.create2_prefix, CREATE2_PREFIX);
+    }
+
+    #[test]
+    fn create2_begins_with_0x41() {
+        // keccak256(0x41 ++ factory ++ salt ++ keccak256(0x60006000f3))[12..],
+        // computed independently
+        let settings = RunSettings {
+            factory_address: USDT_HEX,
+            hashed_salt: None,
+            derivation: Derivation::Create2,
+            create2_prefix: CREATE2_PREFIX,
+        };
+        let init_code_hash = crate::keccak256(&hex!("60006000f3"));
+        let address = settings.derive_address(&init_code_hash, &[0x5a; 32]);
+        let expected: Address = "0x1cb415972fE94263423f86501500795591E30EcC"
+            .parse()
+            .unwrap();
+        assert_eq!(address, expected);
+
+        // the same salt on Ethereum lands elsewhere
+        let ethereum = RunSettings {
+            create2_prefix: 0xff,
+            ..settings
+        };
+        let expected: Address = "0x245D409CfBDa020d4ef9B13cd9277494D3D3257D"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            ethereum.derive_address(&init_code_hash, &[0x5a; 32]),
+            expected
+        );
+    }
+}
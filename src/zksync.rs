@@ -0,0 +1,140 @@
+use crate::error::{Argument, ConfigError};
+use crate::keccak256;
+use sha2::{Digest, Sha256};
+
+/// Version byte of the bytecode hashes that zkSync Era currently deploys.
+const BYTECODE_HASH_VERSION: u8 = 1;
+
+/// The prefix of zkSync Era's CREATE2 preimage, `keccak256("zksyncCreate2")`,
+/// which takes the place of the `0xff` byte.
+pub(crate) fn create2_prefix() -> [u8; 32] {
+    keccak256(b"zksyncCreate2")
+}
+
+/// Compute the versioned hash that zkSync Era identifies bytecode by (as
+/// compiled by zksolc into e.g. a `.zbin` file): the SHA-256 hash of the
+/// bytecode, with its first four bytes replaced by the version and the length
+/// of the bytecode in 32-byte words.
+pub(crate) fn bytecode_hash(argument: Argument, bytecode: &[u8]) -> Result<[u8; 32], ConfigError> {
+    let invalid = |reason| ConfigError::InvalidInitCode { argument, reason };
+
+    if bytecode.len() % 32 != 0 {
+        return Err(invalid(
+            "zkSync bytecode must be a whole number of 32-byte words",
+        ));
+    }
+    let words = bytecode.len() / 32;
+    if words % 2 == 0 {
+        return Err(invalid(
+            "zkSync bytecode must be an odd number of 32-byte words",
+        ));
+    }
+    let Ok(words) = u16::try_from(words) else {
+        return Err(invalid(
+            "zkSync bytecode must be fewer than 2^16 32-byte words",
+        ));
+    };
+
+    let mut hash: [u8; 32] = Sha256::digest(bytecode).into();
+    hash[0] = BYTECODE_HASH_VERSION;
+    hash[1] = 0;
+    hash[2..4].copy_from_slice(&words.to_be_bytes());
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::config;
+    use alloy_primitives::{hex, Address};
+
+    /// Three 32-byte words of bytecode, 0x00 to 0x5f.
+    fn bytecode() -> Vec<u8> {
+        (0..96).collect()
+    }
+
+    #[test]
+    fn prefix_is_zksync_create2_prefix() {
+        // `CREATE2_PREFIX` of zkSync Era's system contracts
+        assert_eq!(
+            create2_prefix(),
+            hex!("2020dba91b30cc0006188af794c2fb30dd8520db7e2c088b7fc7c103c00ca494")
+        );
+    }
+
+    #[test]
+    fn hashes_bytecode_with_version_and_length() {
+        // sha256 of the bytecode is 0x08359b10 8fa567f5..., whose first four
+        // bytes are replaced by the version, a zero byte and the 3 words
+        let hash = bytecode_hash(Argument::InitCode, &bytecode()).unwrap();
+        assert_eq!(
+            hash,
+            hex!("010000038fa567f5dcf319fa3434da6abbc1d595f426372666447f09cc5a87dc")
+        );
+
+        let long = vec![0xab; 32 * 0xffff];
+        assert_eq!(
+            bytecode_hash(Argument::InitCode, &long).unwrap()[..4],
+            [1, 0, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn rejects_bytecode_zksync_would() {
+        for len in [0, 31, 33, 95, 64, 128, 32 * 0x10001] {
+            assert!(
+                matches!(
+                    bytecode_hash(Argument::InitCode, &vec![0; len]),
+                    Err(ConfigError::InvalidInitCode { .. })
+                ),
+                "{len}"
+            );
+        }
+        assert!(bytecode_hash(Argument::InitCode, &[0; 32]).is_ok());
+    }
+
+    #[test]
+    fn derives_zksync_create2_addresses() {
+        // keccak256(prefix ++ sender ++ salt ++ bytecode hash ++
+        // keccak256(constructor input))[12..], computed independently, from
+        // the ContractDeployer itself without constructor input and from
+        // another factory with `constructor(uint256)` given 42
+        let bytecode = format!("0x{}", hex::encode(bytecode()));
+        for (factory, constructor, expected) in [
+            (
+                "0x0000000000000000000000000000000000008006",
+                &[][..],
+                "0x352924CEf577859167Db6EA12188Ab62aa8B772a",
+            ),
+            (
+                "0x1111111111111111111111111111111111111111",
+                &[
+                    "--constructor-sig",
+                    "constructor(uint256)",
+                    "--constructor-args",
+                    "42",
+                ],
+                "0x616d7F7CdD31b9645D7a8b9fEfbf2bc91BEB6F8c",
+            ),
+        ] {
+            let args = [
+                "--zksync",
+                "--factory",
+                factory,
+                "--caller",
+                "0x2222222222222222222222222222222222222222",
+                "--init-code",
+                &bytecode,
+            ];
+            let search = config(&[&args[..], constructor].concat()).unwrap();
+            assert_eq!(
+                search.init_code_hash,
+                bytecode_hash(Argument::InitCode, &hex::decode(&bytecode).unwrap()).unwrap()
+            );
+            assert_eq!(
+                crate::derive_address(&search, &[0x5a; 32]),
+                expected.parse::<Address>().unwrap()
+            );
+        }
+    }
+}